@@ -16,6 +16,7 @@
 # under the License.
 
 from abc import ABCMeta, abstractmethod
+from enum import Enum
 from typing import List
 
 try:
@@ -29,15 +30,20 @@ from ._internal import (
     AggregateUDF,
     Config,
     DataFrame,
+    ExecutionPlan,
+    LogicalPlan,
     SessionContext,
     SessionConfig,
     RuntimeConfig,
     ScalarUDF,
     SQLOptions,
+    WindowUDF,
+    plan_diff,
 )
 
 from .common import (
     DFSchema,
+    ScalarValue,
 )
 
 from .expr import (
@@ -91,6 +97,8 @@ __version__ = importlib_metadata.version(__name__)
 __all__ = [
     "Config",
     "DataFrame",
+    "LogicalPlan",
+    "ExecutionPlan",
     "SessionContext",
     "SessionConfig",
     "SQLOptions",
@@ -98,13 +106,17 @@ __all__ = [
     "Expr",
     "AggregateUDF",
     "ScalarUDF",
+    "WindowUDF",
     "Window",
     "WindowFrame",
     "column",
     "literal",
+    "plan_diff",
+    "TableProviderFilterPushDown",
     "TableScan",
     "Projection",
     "DFSchema",
+    "ScalarValue",
     "DFField",
     "Analyze",
     "Sort",
@@ -159,7 +171,10 @@ class Accumulator(metaclass=ABCMeta):
         pass
 
     @abstractmethod
-    def merge(self, states: pa.Array) -> None:
+    def merge(self, *states: pa.Array) -> None:
+        """
+        Merge the states of other accumulators, one array per state field
+        """
         pass
 
     @abstractmethod
@@ -167,6 +182,29 @@ class Accumulator(metaclass=ABCMeta):
         pass
 
 
+class TableProviderFilterPushDown(str, Enum):
+    """
+    Answer of a Python table provider's `supports_filters_pushdown` for a filter
+    """
+
+    # the provider applies the filter, DataFusion does not re-apply it
+    EXACT = "exact"
+    # the provider may use the filter to skip data, DataFusion re-applies it
+    INEXACT = "inexact"
+    # the filter is not passed to the provider
+    UNSUPPORTED = "unsupported"
+
+
+class WindowEvaluator(metaclass=ABCMeta):
+    @abstractmethod
+    def evaluate_all(self, values: List[pa.Array], num_rows: int) -> pa.Array:
+        """
+        Evaluate the window function over the ordered values of a partition,
+        returning one value per input row
+        """
+        pass
+
+
 def column(value):
     return Expr.column(value)
 
@@ -175,7 +213,7 @@ col = column
 
 
 def literal(value):
-    if not isinstance(value, pa.Scalar):
+    if not isinstance(value, (pa.Scalar, ScalarValue)):
         value = pa.scalar(value)
     return Expr.literal(value)
 
@@ -203,6 +241,9 @@ def udf(func, input_types, return_type, volatility, name=None):
 def udaf(accum, input_type, return_type, state_type, volatility, name=None):
     """
     Create a new User Defined Aggregate Function
+
+    ``state_type`` is a data type, a list of data types or a ``pyarrow.Schema``
+    with one field per value returned by ``Accumulator.state``.
     """
     if not issubclass(accum, Accumulator):
         raise TypeError("`accum` must implement the abstract base class Accumulator")
@@ -210,6 +251,10 @@ def udaf(accum, input_type, return_type, state_type, volatility, name=None):
         name = accum.__qualname__.lower()
     if isinstance(input_type, pa.lib.DataType):
         input_type = [input_type]
+    if isinstance(state_type, pa.lib.DataType):
+        state_type = [state_type]
+    elif isinstance(state_type, pa.Schema):
+        state_type = state_type.types
     return AggregateUDF(
         name=name,
         accumulator=accum,
@@ -218,3 +263,24 @@ def udaf(accum, input_type, return_type, state_type, volatility, name=None):
         state_type=state_type,
         volatility=volatility,
     )
+
+
+def udwf(evaluator, input_type, return_type, volatility, name=None):
+    """
+    Create a new User Defined Window Function
+    """
+    if not issubclass(evaluator, WindowEvaluator):
+        raise TypeError(
+            "`evaluator` must implement the abstract base class WindowEvaluator"
+        )
+    if name is None:
+        name = evaluator.__qualname__.lower()
+    if isinstance(input_type, pa.lib.DataType):
+        input_type = [input_type]
+    return WindowUDF(
+        name=name,
+        evaluator=evaluator,
+        input_types=input_type,
+        return_type=return_type,
+        volatility=volatility,
+    )
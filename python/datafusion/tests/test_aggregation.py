@@ -15,6 +15,8 @@
 # specific language governing permissions and limitations
 # under the License.
 
+import json
+
 import numpy as np
 import pyarrow as pa
 import pytest
@@ -141,3 +143,184 @@ def test_bool_and_or(df):
     result = result[0]
     assert result.column(0) == pa.array([False])
     assert result.column(1) == pa.array([True])
+
+
+def test_array_agg_sorted_unique_tags():
+    ctx = SessionContext()
+    batch = pa.RecordBatch.from_arrays(
+        [
+            pa.array(["a", "a", "a", "a", "b", "b", "c"]),
+            pa.array(["y", "x", "y", None, None, "z", None]),
+        ],
+        names=["grp", "tag"],
+    )
+    df = ctx.create_dataframe([[batch]])
+
+    def tags(ascending):
+        return f.array_agg(
+            column("tag"),
+            distinct=True,
+            order_by=[column("tag").sort(ascending=ascending)],
+        )
+
+    df = df.aggregate(
+        [column("grp")], [tags(True).alias("up"), tags(False).alias("down")]
+    ).sort(column("grp").sort(ascending=True))
+
+    assert df.to_pydict() == {
+        "grp": ["a", "b", "c"],
+        "up": [["x", "y"], ["z"], []],
+        "down": [["y", "x"], ["z"], []],
+    }
+
+
+def test_array_agg_distinct_order_by_other_expression(df):
+    with pytest.raises(Exception, match="may only sort by the aggregated expression"):
+        f.array_agg(column("a"), distinct=True, order_by=[column("b").sort()])
+
+
+def test_array_agg_keeps_nulls():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"g": ["a", "a", "b"], "pos": [2, 1, 1], "v": [1, None, None]})
+
+    result = (
+        df.aggregate(
+            [column("g")],
+            [f.array_agg(column("v"), order_by=[column("pos").sort()]).alias("v")],
+        )
+        .sort(column("g").sort(ascending=True))
+        .to_pydict()
+    )
+
+    assert result == {"g": ["a", "b"], "v": [[None, 1], [None]]}
+
+
+def test_sum_widens_narrow_types():
+    ctx = SessionContext()
+    batch = pa.RecordBatch.from_arrays(
+        [
+            pa.array([2**31 - 1] * 1000, type=pa.int32()),
+            pa.array([2**32 - 1] * 1000, type=pa.uint32()),
+            pa.array([99999.99] * 1000, type=pa.decimal128(7, 2)),
+        ],
+        names=["i", "u", "d"],
+    )
+    df = ctx.create_dataframe([[batch]])
+
+    result = df.aggregate(
+        [], [f.sum(column("i")), f.sum(column("u")), f.sum(column("d"))]
+    ).collect()[0]
+
+    assert result.column(0).type == pa.int64()
+    assert result.column(0)[0].as_py() == (2**31 - 1) * 1000
+    assert result.column(1).type == pa.uint64()
+    assert result.column(1)[0].as_py() == (2**32 - 1) * 1000
+    assert result.column(2).type == pa.decimal128(17, 2)
+    assert str(result.column(2)[0].as_py()) == "99999990.00"
+
+
+def test_distinct_aggregates(df):
+    col_b = column("b")
+    result = (
+        df.aggregate(
+            [column("d")],
+            [
+                f.count(col_b, distinct=True).alias("count"),
+                f.approx_distinct(col_b).alias("approx"),
+                f.sum(col_b, distinct=True).alias("sum"),
+                f.avg(col_b, distinct=True).alias("avg"),
+            ],
+        )
+        .sort(column("d"))
+        .to_pydict()
+    )
+
+    assert result["count"] == [1, 1]
+    assert result["approx"] == result["count"]
+    assert result["sum"] == [6, 4]
+    assert result["avg"] == [6.0, 4.0]
+
+
+def test_json_agg():
+    ctx = SessionContext()
+    batch = pa.RecordBatch.from_pydict(
+        {
+            "g": ["a", "a", "b", "b"],
+            "pos": [2, 1, 1, 2],
+            "s": [
+                {"x": 1, "tags": ["p", None]},
+                {"x": None, "tags": []},
+                {"x": 3, "tags": None},
+                None,
+            ],
+        }
+    )
+    df = ctx.create_dataframe([[batch]])
+
+    result = (
+        df.aggregate(
+            [column("g")],
+            [f.json_agg(column("s"), order_by=[column("pos").sort()]).alias("j")],
+        )
+        .sort(column("g"))
+        .to_pydict()
+    )
+
+    assert [json.loads(j) for j in result["j"]] == [
+        [{"x": None, "tags": []}, {"x": 1, "tags": ["p", None]}],
+        [{"x": 3, "tags": None}, None],
+    ]
+
+
+def test_string_agg():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {
+            "g": ["a", "a", "a", "b", "c"],
+            "pos": [3, 1, 2, 1, 1],
+            "s": ["z", "x", None, "y", None],
+        }
+    )
+
+    joined = f.string_agg(column("s"), ", ", order_by=[column("pos").sort()])
+    result = (
+        df.aggregate([column("g")], [joined.alias("joined")])
+        .sort(column("g"))
+        .to_pydict()
+    )
+
+    assert result == {"g": ["a", "b", "c"], "joined": ["x, z", "y", None]}
+
+
+def test_string_agg_different_orderings():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"pos": [2, 1, 3], "s": ["b", "a", "c"]})
+
+    result = df.aggregate(
+        [],
+        [
+            f.string_agg(column("s"), "", order_by=[column("pos").sort()]).alias("up"),
+            f.string_agg(
+                column("s"), "", order_by=[column("pos").sort(ascending=False)]
+            ).alias("down"),
+        ],
+    ).to_pydict()
+
+    assert result == {"up": ["abc"], "down": ["cba"]}
+
+
+def test_rollup_and_cube():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"k": ["x", "x", "y"], "s": ["p", "q", "p"], "v": [1, 2, 3]})
+
+    rolled = df.aggregate(
+        [f.rollup(column("k"), column("s"))], [f.sum(column("v")).alias("v")]
+    )
+    # (k, s), (k), ()
+    assert rolled.count() == 3 + 2 + 1
+
+    cubed = df.aggregate(
+        [f.cube(column("k"), column("s"))], [f.sum(column("v")).alias("v")]
+    )
+    # (k, s), (k), (s), ()
+    assert cubed.count() == 3 + 2 + 2 + 1
@@ -16,6 +16,7 @@
 # under the License.
 
 import pyarrow as pa
+import pyarrow.parquet as pq
 import pytest
 
 
@@ -38,3 +39,26 @@ def test_basic(ctx, database):
             pa.field("float", pa.float64(), nullable=True),
         ]
     )
+
+
+def test_navigation(ctx, tmp_path):
+    path = tmp_path / "test.parquet"
+    pq.write_table(pa.table({"a": [1, 2, 3]}), path)
+    ctx.register_parquet("events", str(path))
+
+    assert ctx.catalog_names() == ["datafusion"]
+
+    catalog = ctx.catalog("datafusion")
+    assert catalog.schema_names() == ["public"]
+
+    schema = catalog.schema("public")
+    assert schema.table_names() == ["events"]
+    assert schema.table_exist("events")
+    assert not schema.table_exist("missing")
+
+    table = schema.table("events")
+    assert table.kind == "physical"
+    assert table.schema == pa.schema([pa.field("a", pa.int64())])
+
+    ctx.deregister_table("events")
+    assert schema.table_names() == []
@@ -16,8 +16,10 @@
 # under the License.
 import gzip
 import os
+import re
 
 import pyarrow as pa
+import pyarrow.compute as pc
 import pyarrow.dataset as ds
 import pytest
 
@@ -27,6 +29,7 @@ from datafusion import (
     SessionConfig,
     SessionContext,
     SQLOptions,
+    TableProviderFilterPushDown,
     column,
     literal,
 )
@@ -58,6 +61,108 @@ def test_create_context_with_all_valid_args():
         ctx.catalog("datafusion")
 
 
+def test_session_config_partitioning():
+    config = (
+        SessionConfig()
+        .with_target_partitions(1)
+        .with_batch_size(1024)
+        .with_repartition_joins(False)
+        .with_coalesce_batches(False)
+    )
+    ctx = SessionContext(config)
+    ctx.register_csv("csv", "testing/data/csv/aggregate_test_100.csv")
+
+    plan = ctx.sql("SELECT c1, c2 FROM csv WHERE c2 > 1").execution_plan()
+
+    assert plan.partition_count == 1
+    assert "RepartitionExec" not in plan.display_indent()
+    assert "CoalesceBatchesExec" not in plan.display_indent()
+
+
+def test_execute_partition():
+    ctx = SessionContext(SessionConfig().with_target_partitions(4))
+    batches = [pa.RecordBatch.from_pydict({"a": [i, i + 1]}) for i in range(0, 8, 2)]
+    ctx.register_record_batches("t", [[b] for b in batches])
+
+    plan = ctx.table("t").execution_plan()
+    assert plan.partition_count == 4
+
+    rows = 0
+    for part in range(plan.partition_count):
+        stream = ctx.execute(plan, part)
+        batch = stream.next()
+        while batch is not None:
+            rows += batch.to_pyarrow().num_rows
+            batch = stream.next()
+    assert rows == 8
+
+    with pytest.raises(ValueError, match="Partition 4 is out of range"):
+        ctx.execute(plan, 4)
+
+
+def _sort_with_memory_limit(runtime):
+    config = (
+        SessionConfig()
+        .with_target_partitions(1)
+        .set("datafusion.execution.sort_spill_reservation_bytes", str(1024 * 1024))
+    )
+    ctx = SessionContext(config, runtime)
+
+    values = list(range(1_000_000, 0, -1))
+    batches = [
+        pa.RecordBatch.from_arrays([pa.array(values[i : i + 10_000])], names=["a"])
+        for i in range(0, len(values), 10_000)
+    ]
+    return ctx.create_dataframe([batches]).sort(column("a").sort(ascending=True))
+
+
+def test_memory_limit_spills_sort(tmp_path, capsys):
+    runtime = (
+        RuntimeConfig()
+        .with_memory_limit(4 * 1024 * 1024, 1.0)
+        .with_temp_file_path(str(tmp_path))
+    )
+    df = _sort_with_memory_limit(runtime)
+
+    result = pa.Table.from_batches(df.collect())
+    assert result.column("a").to_pylist() == list(range(1, 1_000_001))
+
+    df.explain(analyze=True)
+    assert re.search(r"spill_count=[1-9]", capsys.readouterr().out)
+
+
+def test_memory_limit_exceeded_without_spilling():
+    runtime = (
+        RuntimeConfig()
+        .with_disk_manager_disabled()
+        .with_memory_limit(4 * 1024 * 1024, 1.0)
+    )
+    df = _sort_with_memory_limit(runtime)
+
+    with pytest.raises(Exception, match="Exhausted"):
+        df.collect()
+
+
+def test_memory_pool_peak():
+    limit = 64 * 1024 * 1024
+    runtime = RuntimeConfig().with_greedy_memory_pool(limit)
+    ctx = SessionContext(SessionConfig().with_target_partitions(1), runtime)
+
+    values = pa.array(range(1_000_000, 0, -1), type=pa.int64())
+    batches = [pa.RecordBatch.from_arrays([values], names=["a"])]
+    df = ctx.create_dataframe([batches]).sort(column("a").sort(ascending=True))
+
+    assert ctx.memory_pool_peak() == 0
+    df.collect()
+
+    # the sort buffers all of its input before producing output
+    assert values.nbytes <= ctx.memory_pool_peak() <= limit
+    assert ctx.memory_pool_reserved() == 0
+
+    ctx.reset_memory_pool_peak()
+    assert ctx.memory_pool_peak() == 0
+
+
 def test_register_record_batches(ctx):
     # create a RecordBatch and register it as memtable
     batch = pa.RecordBatch.from_arrays(
@@ -75,6 +180,22 @@ def test_register_record_batches(ctx):
     assert result[0].column(1) == pa.array([-3, -3, -3])
 
 
+def test_register_record_batches_partitions(ctx):
+    first = pa.RecordBatch.from_pydict({"a": [1, 2, 3]})
+    second = pa.RecordBatch.from_pydict({"a": [4, 5]})
+
+    ctx.register_record_batches("t", [[first, first], [second]])
+
+    df = ctx.table("t")
+    assert df.count() == 8
+    assert [len(p) for p in df.collect_partitioned()] == [2, 1]
+
+    other = pa.RecordBatch.from_pydict({"a": ["x"]})
+    with pytest.raises(ValueError, match="batch 0 of partition 1 has schema"):
+        ctx.register_record_batches("u", [[first], [other]])
+    with pytest.raises(ValueError, match="At least one record batch"):
+        ctx.register_record_batches("u", [[]])
+
 def test_create_dataframe_registers_unique_table_name(ctx):
     # create a RecordBatch and register it as memtable
     batch = pa.RecordBatch.from_arrays(
@@ -263,6 +384,197 @@ def test_deregister_table(ctx, database):
     assert public.names() == {"csv1", "csv2"}
 
 
+def test_table_lifecycle(ctx):
+    ctx.from_pydict({"a": [1]}, name="t")
+    assert ctx.table_exists("t")
+
+    assert ctx.deregister_table("t") is True
+    assert not ctx.table_exists("t")
+    with pytest.raises(Exception, match="not found"):
+        ctx.sql("SELECT * FROM t")
+
+    assert ctx.deregister_table("t") is False
+
+
+def test_empty_table(ctx):
+    schema = pa.schema([("a", pa.int64()), ("b", pa.string())])
+    empty = ctx.empty_table(schema, name="empty")
+    assert ctx.tables() == {"empty"}
+    assert empty.schema() == schema
+    assert empty.count() == 0
+
+    df = ctx.from_pydict({"a": [1, 2], "c": [3.0, 4.0]}, name="t")
+    joined = ctx.sql("SELECT * FROM t JOIN empty ON t.a = empty.a")
+    assert joined.schema().names == ["a", "c", "a", "b"]
+    assert joined.count() == 0
+
+    union = df.select_columns("a").union(empty.select_columns("a"))
+    assert union.count() == 2
+
+    no_columns = ctx.empty_table(pa.schema([]))
+    assert len(no_columns.schema()) == 0
+    assert no_columns.count() == 0
+
+
+def test_custom_optimizer_rules(ctx):
+    ctx.from_pydict({"a": [1, 2, 3]}, name="t")
+    filtered = ctx.sql("SELECT a FROM t WHERE a > 1").logical_plan()
+
+    class FilterRule:
+        def name(self):
+            return "filter_rule"
+
+        def try_optimize(self, plan):
+            if "Filter" in plan.display_indent():
+                return None
+            return filtered
+
+    class CountingRule:
+        def __init__(self):
+            self.calls = 0
+
+        def analyze(self, plan):
+            self.calls += 1
+            return None
+
+    counting = CountingRule()
+    ctx.add_optimizer_rule(FilterRule())
+    ctx.add_analyzer_rule(counting)
+
+    df = ctx.sql("SELECT a FROM t")
+    assert "Filter" in df.optimized_logical_plan().display_indent()
+    assert df.to_pydict() == {"a": [2, 3]}
+    assert counting.calls > 0
+
+
+def test_custom_rules_keep_session_time_zone(ctx):
+    class NoopRule:
+        def try_optimize(self, plan):
+            return None
+
+        def analyze(self, plan):
+            return None
+
+    ctx.set_config("datafusion.execution.time_zone", "+14:00")
+    ctx.add_optimizer_rule(NoopRule())
+    ctx.add_analyzer_rule(NoopRule())
+    ctx.set_config("datafusion.execution.time_zone", "-03:00")
+
+    batch = ctx.sql("SELECT now() AS n").collect()[0]
+    assert batch.column(0).type == pa.timestamp("ns", tz="-03:00")
+
+
+def test_register_udtf(ctx):
+    def series(start, stop, step=1):
+        return pa.table({"value": pa.array(range(start, stop, step), pa.int64())})
+
+    def pairs(n):
+        return [pa.RecordBatch.from_pydict({"a": list(range(n)), "b": ["x"] * n})]
+
+    ctx.register_udtf("series", series)
+    ctx.register_udtf("pairs", pairs)
+
+    result = ctx.sql("SELECT * FROM series(1, 10, 3)").to_pydict()
+    assert result == {"value": [1, 4, 7]}
+
+    result = ctx.sql("SELECT sum(a) AS total FROM pairs(4)").to_pydict()
+    assert result == {"total": [6]}
+
+
+def test_register_udtf_invalid_arguments(ctx):
+    ctx.register_udtf("series", lambda start, stop: pa.table({"value": [start]}))
+
+    with pytest.raises(Exception, match="Invalid arguments for table function series"):
+        ctx.sql("SELECT * FROM series(1)")
+
+    with pytest.raises(Exception, match="must be literals"):
+        ctx.sql("SELECT * FROM series(1, abs(2))")
+
+
+class InMemoryProvider:
+    """Table provider that records the scans it receives and ignores filters."""
+
+    def __init__(self, table):
+        self.table = table
+        self.scans = []
+
+    def schema(self):
+        return self.table.schema
+
+    def supports_filters_pushdown(self, filters):
+        # claim filters on "a" without applying them, DataFusion must re-apply them
+        return ["inexact" if "a" in str(f) else "unsupported" for f in filters]
+
+    def scan(self, projection, filters, limit):
+        self.scans.append((projection, [str(f) for f in filters], limit))
+        table = self.table
+        if projection is not None:
+            table = table.select(projection)
+        return table.to_batches(max_chunksize=2)
+
+
+def test_register_table_provider(ctx):
+    provider = InMemoryProvider(
+        pa.table({"a": [1, 2, 3, 4, 5], "b": [10, 20, 30, 40, 50], "c": ["x"] * 5})
+    )
+    ctx.register_table_provider("t", provider)
+
+    result = ctx.sql("SELECT b FROM t WHERE a > 2 AND b < 50 ORDER BY b").to_pydict()
+    assert result == {"b": [30, 40]}
+
+    [(projection, filters, limit)] = provider.scans
+    assert projection == [0, 1]
+    assert len(filters) == 1 and "a" in filters[0]
+    assert limit is None
+
+
+class GreaterThanProvider(InMemoryProvider):
+    """Applies filters of the form `a > <literal>` itself."""
+
+    def __init__(self, table, pushdown):
+        super().__init__(table)
+        self.pushdown = pushdown
+
+    def supports_filters_pushdown(self, filters):
+        return [self.pushdown for _ in filters]
+
+    def scan(self, projection, filters, limit):
+        self.scans.append((projection, [str(f) for f in filters], limit))
+        table = self.table
+        for f in filters:
+            bound = f.to_variant().right().to_variant().value_i64()
+            table = table.filter(pc.greater(table["a"], bound))
+        if projection is not None:
+            table = table.select(projection)
+        return table
+
+
+@pytest.mark.parametrize(
+    "pushdown, has_filter_exec",
+    [
+        (TableProviderFilterPushDown.EXACT, False),
+        (TableProviderFilterPushDown.INEXACT, True),
+        (TableProviderFilterPushDown.UNSUPPORTED, True),
+    ],
+)
+def test_table_provider_filter_pushdown(ctx, pushdown, has_filter_exec):
+    provider = GreaterThanProvider(pa.table({"a": [1, 2, 3, 4]}), pushdown)
+    ctx.register_table_provider("t", provider)
+
+    df = ctx.sql("SELECT a FROM t WHERE a > 2")
+    plan = df.execution_plan().display_indent()
+
+    assert ("FilterExec" in plan) == has_filter_exec
+    if pushdown == TableProviderFilterPushDown.EXACT:
+        assert "exact_filters=[t.a > Int64(2)], inexact_filters=[]" in plan
+    elif pushdown == TableProviderFilterPushDown.INEXACT:
+        assert "exact_filters=[], inexact_filters=[t.a > Int64(2)]" in plan
+    else:
+        assert "exact_filters=[], inexact_filters=[]" in plan
+
+    assert df.to_pydict() == {"a": [3, 4]}
+
+
 def test_register_dataset(ctx):
     # create a RecordBatch and register it as a pyarrow.dataset.Dataset
     batch = pa.RecordBatch.from_arrays(
@@ -423,6 +735,16 @@ def test_read_avro(ctx):
     csv_df.show()
 
 
+def test_read_file_schemas(ctx):
+    path = "parquet/data/alltypes_plain.parquet"
+    assert ctx.read_parquet_schema(path) == ctx.read_parquet(path).schema()
+
+    path = "testing/data/avro/alltypes_plain.avro"
+    assert ctx.read_avro_schema(path) == ctx.read_avro(path).schema()
+
+    path = "testing/data/csv/aggregate_test_100.csv"
+    assert ctx.read_csv_schema(path) == ctx.read_csv(path).schema()
+
 def test_create_sql_options():
     SQLOptions()
 
@@ -450,6 +772,79 @@ def test_sql_with_options_no_dml(ctx):
         ctx.sql_with_options(sql, options=options)
 
 
+def test_sql_dml_into_read_only_table_is_lazy(ctx):
+    batch = pa.RecordBatch.from_arrays([pa.array([1, 2, 3])], names=["a"])
+    ctx.register_dataset("t", ds.dataset([batch]))
+
+    # the insert cannot be planned, so it fails when collected rather than in sql
+    df = ctx.sql("INSERT INTO t VALUES (4)")
+    with pytest.raises(Exception, match="not implemented"):
+        df.collect()
+    assert ctx.table("t").count() == 3
+
+
+def test_sql_with_options_flags(ctx):
+    ctx.sql("CREATE TABLE t AS VALUES (1), (2)")
+
+    kwargs = {"allow_ddl": False, "allow_dml": False, "allow_statements": False}
+    df = ctx.sql_with_options("SELECT * FROM t", **kwargs)
+    assert df.count() == 2
+
+    with pytest.raises(Exception, match="DDL not supported"):
+        ctx.sql_with_options("DROP TABLE t", **kwargs)
+    with pytest.raises(Exception, match="DDL not supported"):
+        ctx.sql_with_options("CREATE TABLE u AS VALUES (1)", **kwargs)
+    assert ctx.table_exist("t")
+    assert not ctx.table_exist("u")
+
+    # the flags override the options they are combined with
+    options = SQLOptions().with_allow_ddl(False)
+    ctx.sql_with_options("DROP TABLE t", options=options, allow_ddl=True)
+    assert not ctx.table_exist("t")
+
+
+def test_register_view(ctx):
+    ctx.sql("CREATE TABLE t (a BIGINT)")
+    ctx.sql("INSERT INTO t VALUES (1), (2), (3)")
+    df = ctx.table("t").filter(column("a") > literal(1))
+
+    ctx.register_view("v", df)
+    result = ctx.sql("SELECT a FROM v ORDER BY a").to_pydict()
+    assert result == {"a": [2, 3]}
+
+    # the view is planned again on each query and sees new rows
+    ctx.sql("INSERT INTO t VALUES (0), (4)")
+    result = ctx.sql("SELECT a FROM v ORDER BY a").to_pydict()
+    assert result == {"a": [2, 3, 4]}
+
+    with pytest.raises(ValueError, match="Table 'v' already exists"):
+        ctx.register_view("v", ctx.table("t"))
+
+    ctx.register_view("v", ctx.table("t"), or_replace=True)
+    assert ctx.sql("SELECT COUNT(*) AS n FROM v").to_pydict() == {"n": [5]}
+
+
+def test_sql_create_table_as_select(ctx):
+    df = ctx.sql("CREATE TABLE t AS VALUES (1, 'a'), (2, 'b'), (3, 'c')")
+
+    # the statement has already been executed, the table is queryable
+    assert df.count() == 0
+    assert ctx.table_exist("t")
+    assert ctx.table("t").count() == 3
+
+
+def test_sql_dml_executes_eagerly(ctx):
+    ctx.sql("CREATE TABLE t (a BIGINT, b VARCHAR)")
+
+    # the insert happens without collecting the returned DataFrame
+    df = ctx.sql("INSERT INTO t VALUES (1, 'a'), (2, 'b')")
+    assert ctx.table("t").count() == 2
+
+    # the returned DataFrame reports the number of inserted rows
+    assert df.to_pydict() == {"count": [2]}
+    assert ctx.table("t").count() == 2
+
+
 def test_sql_with_options_no_statements(ctx):
     sql = "SET time zone = 1;"
     ctx.sql(sql)
@@ -14,7 +14,11 @@
 # KIND, either express or implied.  See the License for the
 # specific language governing permissions and limitations
 # under the License.
+import _thread
 import os
+import threading
+import time
+from datetime import datetime
 
 import pyarrow as pa
 import pyarrow.parquet as pq
@@ -23,12 +27,17 @@ import pytest
 from datafusion import functions as f
 from datafusion import (
     DataFrame,
+    ExecutionPlan,
+    LogicalPlan,
+    SessionConfig,
     SessionContext,
     WindowFrame,
     column,
     literal,
+    plan_diff,
     udf,
 )
+from datafusion.expr import WindowFrameBound
 
 
 @pytest.fixture
@@ -96,6 +105,31 @@ def test_select(df):
     assert result.column(1) == pa.array([-3, -3, -3])
 
 
+def test_schema_metadata():
+    ctx = SessionContext()
+    schema = pa.schema(
+        [
+            pa.field("a", pa.int64(), nullable=False, metadata={"unit": "m"}),
+            pa.field("b", pa.string()),
+        ],
+        metadata={"source": "sensor"},
+    )
+    batch = pa.RecordBatch.from_pydict({"a": [1, 2], "b": ["x", "y"]}, schema=schema)
+    ctx.register_record_batches("t", [[batch]])
+
+    df = ctx.sql("SELECT t.a, t.b FROM t")
+    result = df.schema()
+    assert result.names == ["a", "b"]
+    assert result.metadata == {b"source": b"sensor"}
+    assert result.field("a").metadata == {b"unit": b"m"}
+    assert not result.field("a").nullable
+    assert result.field("b").nullable
+    assert df.schema_qualifiers() == ["t", "t"]
+
+    computed = df.select(column("a") + literal(1))
+    assert computed.schema_qualifiers() == [None]
+
+
 def test_select_columns(df):
     df = df.select_columns("b", "a")
 
@@ -106,6 +140,36 @@ def test_select_columns(df):
     assert result.column(1) == pa.array([1, 2, 3])
 
 
+def test_select_columns_patterns():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {"id": [1], "sensor_b": [2], "temp": [3], "sensor_a": [4], "sensor_1": [5]}
+    )
+
+    assert df.select_columns("sensor_*").schema().names == [
+        "sensor_b",
+        "sensor_a",
+        "sensor_1",
+    ]
+    assert df.select_columns("temp", "sensor_?", "id").schema().names == [
+        "temp",
+        "sensor_b",
+        "sensor_a",
+        "sensor_1",
+        "id",
+    ]
+    result = df.select_columns("^sensor_[a-z]$", "temp", regex=True)
+    assert result.schema().names == ["sensor_b", "sensor_a", "temp"]
+    assert df.select_columns("sensor_a", "sensor_*").schema().names == [
+        "sensor_a",
+        "sensor_b",
+        "sensor_1",
+    ]
+
+    with pytest.raises(ValueError, match="Pattern 'humidity_\\*' matches no columns"):
+        df.select_columns("humidity_*")
+
+
 def test_filter(df):
     df = df.filter(column("a") > literal(2)).select(
         column("a") + column("b"),
@@ -119,6 +183,16 @@ def test_filter(df):
     assert result.column(1) == pa.array([-3])
 
 
+def test_filter_multiple_predicates(df):
+    result = df.filter(column("a") > literal(1), column("b") < literal(6))
+    assert result.to_pydict() == {"a": [2], "b": [5], "c": [8]}
+
+    assert df.filter().to_pydict() == df.to_pydict()
+
+    with pytest.raises(TypeError, match="returns Int64, expected a boolean"):
+        df.filter(column("a") + column("b"))
+
+
 def test_sort(df):
     df = df.sort(column("b").sort(ascending=False))
 
@@ -128,6 +202,409 @@ def test_sort(df):
     assert table.to_pydict() == expected
 
 
+def test_cast_to_schema(ctx):
+    df = ctx.from_pydict({"b": ["1", "x", "3"], "a": [1, 2, 3], "extra": [0, 0, 0]})
+    target = pa.schema(
+        [
+            pa.field("a", pa.float64(), metadata={"unit": "m"}),
+            pa.field("b", pa.int32()),
+            pa.field("c", pa.string()),
+        ],
+        metadata={"source": "test"},
+    )
+
+    with pytest.raises(ValueError, match="Column 'c' of the target schema is missing"):
+        df.cast_to_schema(target)
+
+    # "x" is not a number: a strict cast fails, a try cast gives NULL
+    with pytest.raises(Exception, match="Cannot cast string 'x'"):
+        df.cast_to_schema(target, fill_missing=True).collect()
+
+    result = df.cast_to_schema(target, safe=False, fill_missing=True)
+    assert result.schema() == target
+    assert result.schema().metadata == {b"source": b"test"}
+    assert result.schema().field("a").metadata == {b"unit": b"m"}
+    assert result.to_pydict() == {
+        "a": [1.0, 2.0, 3.0],
+        "b": [1, None, 3],
+        "c": [None, None, None],
+    }
+
+
+def test_with_columns_map(df):
+    df = df.with_columns_map(
+        {
+            "d": column("a") + column("b"),
+            "b": column("b") * literal(10),
+            "e": literal("x"),
+        }
+    )
+
+    assert df.schema().names == ["a", "b", "c", "d", "e"]
+    assert df.to_pydict() == {
+        "a": [1, 2, 3],
+        "b": [40, 50, 60],
+        "c": [8, 5, 8],
+        "d": [5, 7, 9],
+        "e": ["x", "x", "x"],
+    }
+
+
+def test_with_columns(df):
+    df = df.with_columns(
+        [
+            ("d", column("a") + column("b")),
+            ("b", column("b") * literal(10)),
+        ],
+        e=column("d") + column("b"),
+    )
+
+    assert df.schema().names == ["a", "b", "c", "d", "e"]
+    assert df.to_pydict() == {
+        "a": [1, 2, 3],
+        "b": [40, 50, 60],
+        "c": [8, 5, 8],
+        "d": [5, 7, 9],
+        "e": [45, 57, 69],
+    }
+
+    with pytest.raises(ValueError, match="given more than once"):
+        df.with_columns([("x", literal(1)), ("x", literal(2))])
+
+
+def test_group_by_time(ctx):
+    ts = [
+        datetime(2021, 1, 1, 0, 5),
+        datetime(2021, 1, 1, 0, 40),
+        datetime(2021, 1, 1, 0, 55),
+        datetime(2021, 1, 1, 2, 10),
+    ]
+    df = ctx.from_pydict({"ts": ts, "v": [1, 2, 3, 4]})
+
+    hourly = df.group_by_time(
+        "ts", "1 hour", [f.count(column("v")).alias("n")]
+    ).to_pydict()
+    # the bucket starting at 01:00 has no rows and is absent
+    assert hourly == {
+        "ts": [datetime(2021, 1, 1, 0), datetime(2021, 1, 1, 2)],
+        "n": [3, 1],
+    }
+
+    aligned = df.group_by_time(
+        "ts",
+        "1 hour",
+        [f.sum(column("v")).alias("total")],
+        origin=literal("2021-01-01T00:30:00"),
+    ).to_pydict()
+    assert aligned == {
+        "ts": [
+            datetime(2020, 12, 31, 23, 30),
+            datetime(2021, 1, 1, 0, 30),
+            datetime(2021, 1, 1, 1, 30),
+        ],
+        "total": [1, 5, 4],
+    }
+
+    with pytest.raises(ValueError, match="Invalid interval 'soon'"):
+        df.group_by_time("ts", "soon", [f.count(column("v"))])
+
+
+def test_distinct_on(ctx):
+    df = ctx.from_pydict(
+        {
+            "id": [1, 2, 1, 2, 1],
+            "ts": [
+                datetime(2021, 1, 1),
+                datetime(2021, 1, 3),
+                datetime(2021, 1, 5),
+                datetime(2021, 1, 2),
+                datetime(2021, 1, 4),
+            ],
+            "v": ["a", "b", "c", "d", "e"],
+        }
+    )
+
+    latest = df.distinct_on(
+        [column("id")],
+        sort_exprs=[column("id"), column("ts").sort(ascending=False)],
+    )
+    assert latest.schema().names == ["id", "ts", "v"]
+    assert latest.sort(column("id")).to_pydict() == {
+        "id": [1, 2],
+        "ts": [datetime(2021, 1, 5), datetime(2021, 1, 3)],
+        "v": ["c", "b"],
+    }
+
+    values = df.distinct_on(
+        [column("id")],
+        [column("id"), column("v")],
+        [column("id"), column("ts").sort(ascending=False)],
+    )
+    assert values.sort(column("id")).to_pydict() == {"id": [1, 2], "v": ["c", "b"]}
+
+
+def test_null_counts(tmp_path):
+    path = tmp_path / "nulls.parquet"
+    table = pa.table({"a": [1, None, 3, None], "b": ["x", "y", None, "z"]})
+    pq.write_table(table, path)
+
+    config = SessionConfig().set("datafusion.execution.collect_statistics", "true")
+    ctx = SessionContext(config)
+    ctx.register_parquet("t", str(path))
+    df = ctx.table("t")
+
+    expected = {"a": 2, "b": 1}
+    assert df.null_counts() == expected
+    assert df.null_counts(exact=True) == expected
+
+    # computed columns have no statistics and are counted exactly
+    derived = df.select(column("a"), f.upper(column("b")).alias("c"))
+    assert derived.null_counts() == {"a": 2, "c": 1}
+
+
+def test_select_cols(ctx):
+    df = ctx.from_pydict({"a": [1, 2], "b": [3, 4], "c": [5, 6]}, name="t")
+
+    result = df.select(*f.cols("c", "a"))
+    assert result.to_pydict() == {"c": [5, 6], "a": [1, 2]}
+
+    names = (name for name in ["b", "c"])
+    result = df.select(*f.cols("a", names))
+    assert result.schema().names == ["a", "b", "c"]
+
+    result = df.select(*f.cols(["t.b"]))
+    assert result.to_pydict() == {"b": [3, 4]}
+
+
+def test_last_scan_stats(ctx, tmp_path):
+    path = tmp_path / "scan.parquet"
+    pq.write_table(pa.table({"a": list(range(1000))}), path, row_group_size=100)
+    ctx.register_parquet("t", str(path))
+
+    df = ctx.table("t").filter(column("a") >= literal(950))
+    assert df.last_scan_stats() is None
+
+    assert len(df.to_pydict()["a"]) == 50
+    stats = df.last_scan_stats()
+    assert stats["row_groups_total"] == 10
+    assert stats["row_groups_pruned"] == 9
+    assert stats["rows_scanned"] == 100
+    assert stats["rows_matched"] == 50
+
+
+def test_show_truncate(ctx, capsys):
+    df = ctx.from_pydict(
+        {
+            "s": ["short", "x" * 100],
+            "b": [b"\x00\x01\x02", b""],
+            "l": [list(range(50)), [1]],
+        }
+    )
+
+    df.show(max_width=20)
+    out = capsys.readouterr().out
+    assert "x" * 19 + "…" in out
+    assert "x" * 20 not in out
+    assert "<3 bytes>" in out
+    assert "<list of 50 items>" in out
+    assert "[1]" in out
+
+    df.show(truncate=False)
+    out = capsys.readouterr().out
+    assert "x" * 100 in out
+    assert "<3 bytes>" in out
+
+
+def test_estimated_bytes(ctx):
+    df = ctx.from_pydict(
+        {"a": list(range(1000)), "b": list(range(1000)), "c": list(range(1000))}
+    )
+
+    all_columns = df.estimated_bytes()
+    one_column = df.select_columns("a").estimated_bytes()
+    assert all_columns is not None and one_column is not None
+    assert 0 < one_column < all_columns
+
+
+def test_sample(ctx):
+    df = ctx.from_pydict({"a": list(range(10000))})
+
+    assert 800 < df.sample(0.1).count() < 1200
+    assert df.sample(0.0).count() == 0
+    assert df.sample(1.0).count() == 10000
+
+    first = df.sample(0.1, seed=42).sort(column("a")).to_pydict()
+    second = df.sample(0.1, seed=42).sort(column("a")).to_pydict()
+    assert first == second
+    assert 800 < len(first["a"]) < 1200
+    assert df.sample(0.1, seed=7).sort(column("a")).to_pydict() != first
+
+    # the hash is fixed, so a seeded sample is the same on every machine
+    pinned = ctx.from_pydict({"a": list(range(20))}).sample(0.5, seed=42)
+    expected = [0, 1, 2, 3, 6, 7, 8, 11, 14, 15, 16, 17, 19]
+    assert pinned.sort(column("a")).to_pydict() == {"a": expected}
+
+    with pytest.raises(ValueError, match="between 0 and 1"):
+        df.sample(1.5)
+
+
+def test_write_table(ctx):
+    ctx.sql("CREATE TABLE t (a BIGINT, b VARCHAR)")
+    df = ctx.from_pydict({"a": [1, 2, 3], "b": ["x", "y", "z"]})
+
+    assert df.write_table("t") == 3
+    assert df.filter(column("a") > literal(2)).write_table("t") == 1
+    result = ctx.sql("SELECT a, b FROM t ORDER BY a, b").to_pydict()
+    assert result == {"a": [1, 2, 3, 3], "b": ["x", "y", "z", "z"]}
+
+    ctx.register_view("v", ctx.table("t"))
+    with pytest.raises(ValueError, match="Table 'v' does not support inserts"):
+        df.write_table("v")
+
+
+def test_plan_diff(df):
+    a = df.filter(column("a") > literal(1)).select(column("a"))
+    b = df.filter(column("a") > literal(1)).select(column("a"))
+    assert plan_diff(a, b) == ""
+
+    c = df.filter(column("a") > literal(2)).select(column("a"))
+    diff = plan_diff(a, c).splitlines()
+    assert any(line.startswith("- ") and "Int64(1)" in line for line in diff)
+    assert any(line.startswith("+ ") and "Int64(2)" in line for line in diff)
+    assert any(line.startswith("  ") for line in diff)
+
+
+def test_select_by_dtype():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {"a": [1, 2], "b": ["x", "y"], "c": [1.5, 2.5], "d": [True, False]}
+    )
+
+    assert df.select_by_dtype(["numeric"]).schema().names == ["a", "c"]
+    assert df.select_by_dtype(["integer", pa.bool_()]).schema().names == ["a", "d"]
+    assert df.select_by_dtype([pa.string()]).schema().names == ["b"]
+
+    with pytest.raises(ValueError, match="No columns match"):
+        df.select_by_dtype([pa.date32()])
+
+    with pytest.raises(ValueError, match="Unknown data type family 'numbers'"):
+        df.select_by_dtype(["numbers"])
+
+
+def test_in_subquery(ctx):
+    df = ctx.from_pydict({"a": [1, 2, 3], "b": [4, 5, 6]})
+    subquery = ctx.from_pydict({"x": [1, 3, 7]}).select(column("x"))
+
+    result = df.filter(column("a").in_subquery(subquery)).sort(column("a").sort())
+    assert result.to_pydict()["a"] == [1, 3]
+
+    result = df.filter(column("a").in_subquery(subquery, negated=True))
+    assert result.to_pydict()["a"] == [2]
+
+
+def test_scalar_subquery(ctx):
+    df = ctx.from_pydict({"a": [1, 2, 3], "b": [4, 5, 6]})
+    subquery = ctx.from_pydict({"x": [1, 3, 7]}).aggregate([], [f.max(column("x"))])
+
+    result = df.filter(column("b") < f.scalar_subquery(subquery))
+    assert result.sort(column("a").sort()).to_pydict()["b"] == [4, 5, 6]
+
+    result = df.filter(column("a") == f.scalar_subquery(subquery))
+    assert result.count() == 0
+
+
+def test_sort_multiple_keys():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {"a": [2, None, 1, 2, None], "b": [1, 2, 3, 4, None], "c": [0, 1, 2, 3, 4]}
+    )
+
+    df = df.sort(
+        column("a").sort(ascending=True, nulls_first=False),
+        column("b").sort(ascending=False, nulls_first=True),
+    )
+    assert df.to_pydict()["c"] == [2, 3, 0, 4, 1]
+
+
+def test_sort_plain_expression(df):
+    df = df.sort(column("c"), column("b").sort(ascending=False))
+    assert df.to_pydict()["a"] == [2, 3, 1]
+
+
+def test_top_k(ctx):
+    df = ctx.from_pydict(
+        {
+            "g": ["x", "x", "x", "x", "y", "y", "y", "z"],
+            "v": [3, 1, 3, 3, 5, 7, 6, 2],
+            "id": [8, 1, 2, 3, 4, 5, 6, 7],
+        }
+    )
+
+    top = df.top_k(2, [column("v").sort(ascending=False)], [column("g")])
+    top = top.sort(column("g"), column("id")).to_pydict()
+    # the three-way tie on v=3 in group x is broken by the remaining columns
+    assert top == {
+        "g": ["x", "x", "y", "y", "z"],
+        "v": [3, 3, 7, 6, 2],
+        "id": [2, 3, 5, 6, 7],
+    }
+
+    top = df.top_k(2, [column("v")]).to_pydict()
+    assert top["id"] == [1, 7]
+
+    plan = df.top_k(2, [column("v")]).execution_plan().display_indent()
+    assert "fetch=2" in plan
+
+
+def test_first_last(df):
+    df = df.sort(column("b").sort(ascending=False))
+
+    assert df.first() == {"a": 3, "b": 6, "c": 8}
+    assert df.last() == {"a": 1, "b": 4, "c": 8}
+
+    empty = df.filter(column("a") > literal(10))
+    assert empty.first() is None
+    assert empty.last() is None
+
+
+def test_last_requires_sort(df):
+    assert df.first() is not None
+
+    with pytest.raises(ValueError, match="requires a sorted DataFrame"):
+        df.last()
+
+
+def test_assert_unique():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {"id": [1, 2, 2, 3, 3, 3], "part": ["x", "x", "y", "x", "x", "x"]}
+    )
+
+    df.filter(column("id") < literal(3)).assert_unique(["id", "part"])
+
+    with pytest.raises(ValueError, match=r"\(id=3, part=x\) in 3 rows$"):
+        df.assert_unique(["id", "part"])
+
+    with pytest.raises(ValueError, match="duplicate keys") as exc_info:
+        df.assert_unique(["id"])
+    assert "(id=2) in 2 rows" in str(exc_info.value)
+    assert "(id=3) in 3 rows" in str(exc_info.value)
+
+def test_assert_no_nulls():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {"a": [1, 2, 3], "b": [None, 5, None], "c": ["x", None, "z"]}
+    )
+
+    df.assert_no_nulls(["a"])
+
+    with pytest.raises(ValueError, match=r"b \(2\), c \(1\)"):
+        df.assert_no_nulls()
+
+    with pytest.raises(ValueError, match=r"columns: c \(1\)$"):
+        df.assert_no_nulls(["a", "c"])
+
+
 def test_limit(df):
     df = df.limit(1)
 
@@ -174,6 +651,24 @@ def test_with_column_renamed(df):
     assert result.schema.field(2).name == "sum"
 
 
+def test_rename_columns(df):
+    df = df.rename_columns(["x", "y", "z"])
+
+    result = df.collect()[0]
+
+    assert result.schema.names == ["x", "y", "z"]
+    assert result.column(0) == pa.array([1, 2, 3])
+    assert result.column(2) == pa.array([8, 5, 8])
+
+
+def test_rename_columns_invalid(df):
+    with pytest.raises(ValueError, match="Expected 3 column names but 2"):
+        df.rename_columns(["x", "y"])
+
+    with pytest.raises(ValueError, match="Duplicate column name 'x'"):
+        df.rename_columns(["x", "y", "x"])
+
+
 def test_unnest(nested_df):
     nested_df = nested_df.unnest_column("a")
 
@@ -233,6 +728,139 @@ def test_join():
     assert table.to_pydict() == expected
 
 
+def test_pivot():
+    ctx = SessionContext()
+    sales = ctx.from_pydict(
+        {
+            "year": [2023, 2023, 2023, 2024, 2024],
+            "region": ["east", "west", "east", "west", None],
+            "amount": [10, 20, 5, 7, 100],
+        }
+    )
+
+    result = sales.pivot(["year"], "region", "amount").sort(column("year"))
+    assert result.to_pydict() == {
+        "year": [2023, 2024],
+        "east": [15, None],
+        "west": [20, 7],
+    }
+
+    result = sales.pivot(["year"], "region", "amount", agg="count")
+    assert result.sort(column("year")).to_pydict() == {
+        "year": [2023, 2024],
+        "east": [2, None],
+        "west": [1, 1],
+    }
+
+    result = sales.pivot(
+        ["year"], "region", "amount", agg="count", values=["west", "north"]
+    )
+    assert result.sort(column("year")).to_pydict() == {
+        "year": [2023, 2024],
+        "west": [1, 1],
+        "north": [None, None],
+    }
+
+    with pytest.raises(ValueError, match="Unknown pivot aggregate 'median'"):
+        sales.pivot(["year"], "region", "amount", agg="median")
+
+
+def test_unpivot():
+    ctx = SessionContext()
+    wide = ctx.from_pydict(
+        {
+            "id": [1, 2],
+            "cpu": [0.5, None],
+            "mem": [1, 2],
+            "disk": [3, 4],
+        }
+    )
+
+    long = wide.unpivot(["id"], ["cpu", "mem", "disk"], "metric", "reading")
+    assert long.count() == 2 * 3
+    assert long.schema().field("reading").type == pa.float64()
+
+    rows = sorted(zip(*long.to_pydict().values()), key=lambda row: (row[0], row[1]))
+    assert rows == [
+        (1, "cpu", 0.5),
+        (1, "disk", 3.0),
+        (1, "mem", 1.0),
+        (2, "cpu", None),
+        (2, "disk", 4.0),
+        (2, "mem", 2.0),
+    ]
+
+    named = ctx.from_pydict({"id": [1], "name": ["x"], "size": [3]})
+    with pytest.raises(ValueError, match="cannot combine column 'size'"):
+        named.unpivot(["id"], ["name", "size"])
+
+
+def test_semi_and_anti_joins():
+    ctx = SessionContext()
+    left = ctx.from_pydict({"a": [1, 2, 3], "b": [4, 5, 6]})
+    right = ctx.from_pydict({"a": [1, 2, 7], "c": [8, 10, 12]})
+
+    def joined(how):
+        df = left.join(right, join_keys=(["a"], ["a"]), how=how)
+        return df.sort(column("a")).to_pydict()
+
+    assert joined("left_semi") == {"a": [1, 2], "b": [4, 5]}
+    assert joined("semi") == joined("left_semi")
+    assert joined("left_anti") == {"a": [3], "b": [6]}
+    assert joined("anti") == joined("left_anti")
+    assert joined("right_semi") == {"a": [1, 2], "c": [8, 10]}
+    assert joined("right_anti") == {"a": [7], "c": [12]}
+
+    with pytest.raises(ValueError, match="Unknown join type 'cross'"):
+        left.join(right, join_keys=(["a"], ["a"]), how="cross")
+
+
+def test_join_broadcast_hint():
+    config = (
+        SessionConfig()
+        .with_target_partitions(4)
+        .set("datafusion.optimizer.prefer_hash_join", "false")
+    )
+    ctx = SessionContext(config)
+    left = ctx.from_pydict({"a": list(range(1000)), "b": list(range(1000))})
+    right = ctx.from_pydict({"a": [1, 2], "c": [8, 10]})
+
+    plan = left.join(right, (["a"], ["a"]), "inner").execution_plan()
+    assert "SortMergeJoin" in plan.display_indent()
+
+    df = left.join(right, (["a"], ["a"]), "inner", hint="broadcast")
+    assert "HashJoinExec: mode=CollectLeft" in df.execution_plan().display_indent()
+    result = df.sort(column("c")).to_pydict()
+    assert result["c"] == [8, 10]
+    assert result["b"] == [1, 2]
+
+    with pytest.raises(ValueError, match="Unknown join hint 'shuffle'"):
+        left.join(right, (["a"], ["a"]), "inner", hint="shuffle")
+
+
+def test_join_broadcast_hint_applies_to_one_join():
+    config = (
+        SessionConfig()
+        .with_target_partitions(4)
+        .set("datafusion.optimizer.prefer_hash_join", "false")
+    )
+    ctx = SessionContext(config)
+    left = ctx.from_pydict({"a": list(range(1000)), "b": list(range(1000))})
+    right = ctx.from_pydict({"a": [1, 2], "c": [8, 10]})
+    other = ctx.from_pydict({"b": [1, 2], "d": [3, 4]})
+
+    df = left.join(right, (["a"], ["a"]), "inner", hint="broadcast").join(
+        other, (["b"], ["b"]), "inner"
+    )
+    plan = df.execution_plan().display_indent()
+    assert plan.count("HashJoinExec: mode=CollectLeft") == 1
+    assert "SortMergeJoin" in plan
+    assert df.sort(column("d")).to_pydict()["d"] == [3, 4]
+
+    plan = left.join(right, (["a"], ["a"]), "inner").execution_plan()
+    assert "SortMergeJoin" in plan.display_indent()
+
+
 def test_distinct():
     ctx = SessionContext()
 
@@ -335,6 +963,68 @@ def test_window_functions(df):
     assert table.sort_by("a").to_pydict() == expected
 
 
+def test_ranking_functions(ctx):
+    df = ctx.from_pydict({"g": ["a", "a", "a", "a", "b"], "score": [10, 20, 20, 30, 5]})
+    partition_by = [column("g")]
+    order_by = [f.order_by(column("score"))]
+
+    result = (
+        df.select(
+            column("g"),
+            column("score"),
+            f.row_number(partition_by, order_by).alias("row"),
+            f.rank(partition_by, order_by).alias("rank"),
+            f.dense_rank(partition_by, order_by).alias("dense_rank"),
+            f.percent_rank(partition_by, order_by).alias("percent_rank"),
+            f.cume_dist(partition_by, order_by).alias("cume_dist"),
+            f.ntile(2, partition_by, order_by).alias("ntile"),
+            f.window("sum", [column("score")], partition_by, order_by, ctx=ctx).alias(
+                "running"
+            ),
+        )
+        .sort(column("g"), column("score"))
+        .to_pydict()
+    )
+
+    assert result["rank"] == [1, 2, 2, 4, 1]
+    assert result["dense_rank"] == [1, 2, 2, 3, 1]
+    assert result["percent_rank"] == [0.0, 1 / 3, 1 / 3, 1.0, 0.0]
+    assert result["cume_dist"] == [0.25, 0.75, 0.75, 1.0, 1.0]
+    assert result["ntile"] == [1, 1, 2, 2, 1]
+    assert sorted(result["row"][:4]) == [1, 2, 3, 4]
+    # window() keeps its frame of the rows up to the current one, peers are not
+    # summed together
+    assert sorted(result["running"][:4]) == [10, 30, 50, 80]
+    assert result["running"][4] == 5
+
+
+def test_lag_and_lead(ctx):
+    df = ctx.from_pydict({"g": ["a", "a", "a", "b", "b"], "t": [1, 2, 3, 1, 2]})
+    partition_by = [column("g")]
+    order_by = [f.order_by(column("t"))]
+    t = column("t")
+
+    result = (
+        df.select(
+            column("g"),
+            t,
+            f.lag(t, partition_by=partition_by, order_by=order_by).alias("prev"),
+            f.lag(t, 2, 0.0, partition_by, order_by).alias("prev2"),
+            f.lead(t, default=-1, partition_by=partition_by, order_by=order_by).alias(
+                "next"
+            ),
+            f.lag(t, 0, partition_by=partition_by, order_by=order_by).alias("same"),
+        )
+        .sort(column("g"), t)
+        .to_pydict()
+    )
+
+    assert result["prev"] == [None, 1, 2, None, 1]
+    assert result["prev2"] == [0, 0, 1, 0, 0]
+    assert result["next"] == [2, 3, -1, 2, -1]
+    assert result["same"] == result["t"]
+
+
 @pytest.mark.parametrize(
     ("units", "start_bound", "end_bound"),
     [
@@ -367,6 +1057,48 @@ def test_invalid_window_frame(units, start_bound, end_bound):
         WindowFrame(units, start_bound, end_bound)
 
 
+def test_window_frame_bounds(ctx):
+    df = ctx.from_pydict(
+        {"g": ["a", "a", "a", "b", "b"], "t": [1, 2, 2, 1, 3], "v": [1, 2, 3, 4, 5]}
+    )
+
+    def window_sum(frame, order_by):
+        return f.window(
+            "sum",
+            [column("v")],
+            partition_by=[column("g")],
+            order_by=[f.order_by(column(order_by))],
+            window_frame=frame,
+            ctx=ctx,
+        )
+
+    running = WindowFrame(
+        "range", WindowFrameBound.unbounded_preceding(), WindowFrameBound.current_row()
+    )
+    moving = WindowFrame(
+        "rows", WindowFrameBound.preceding(1), WindowFrameBound.current_row()
+    )
+    rest = WindowFrame(
+        "rows", WindowFrameBound.following(1), WindowFrameBound.unbounded_following()
+    )
+    result = (
+        df.select(
+            column("g"),
+            column("v"),
+            window_sum(running, "t").alias("running"),
+            window_sum(moving, "v").alias("moving"),
+            window_sum(rest, "v").alias("rest"),
+        )
+        .sort(column("v"))
+        .to_pydict()
+    )
+
+    # rows that sort equal to the current row are part of a range frame
+    assert result["running"] == [1, 6, 6, 4, 9]
+    assert result["moving"] == [1, 3, 5, 4, 9]
+    assert result["rest"] == [5, 3, None, 5, None]
+    assert repr(WindowFrameBound.preceding(2)) == "WindowFrameBound(2 PRECEDING)"
+
 def test_get_dataframe(tmp_path):
     ctx = SessionContext()
 
@@ -408,6 +1140,16 @@ def test_explain(df):
     df.explain()
 
 
+def test_explain_analyze(df, capsys):
+    df = df.filter(column("a") > literal(1))
+    df.explain(analyze=True)
+
+    output = capsys.readouterr().out
+    assert "Plan with Metrics" in output
+    assert "FilterExec" in output
+    assert "output_rows=2" in output
+
+
 def test_logical_plan(aggregate_df):
     plan = aggregate_df.logical_plan()
 
@@ -476,6 +1218,111 @@ def test_execution_plan(aggregate_df):
     assert batch is None
 
 
+def test_plan_proto_round_trip():
+    ctx = SessionContext()
+    ctx.register_csv("test", "testing/data/csv/aggregate_test_100.csv")
+
+    is_positive = udf(
+        lambda x: pa.compute.greater(x, 0),
+        [pa.int64()],
+        pa.bool_(),
+        volatility="immutable",
+        name="is_positive",
+    )
+    ctx.register_udf(is_positive)
+
+    df = ctx.sql("select c1, c2 from test where is_positive(c2) order by c1, c2")
+
+    logical_bytes = df.logical_plan().to_proto()
+    assert isinstance(logical_bytes, bytes)
+    logical_plan = LogicalPlan.from_proto(logical_bytes, ctx)
+    round_trip = ctx.create_dataframe_from_logical_plan(logical_plan)
+    assert round_trip.collect() == df.collect()
+
+    physical_bytes = df.execution_plan().to_proto()
+    physical_plan = ExecutionPlan.from_proto(physical_bytes, ctx)
+    assert physical_plan.display_indent() == df.execution_plan().display_indent()
+
+    # a context without the UDF registered cannot resolve the plan
+    with pytest.raises(Exception, match="is_positive"):
+        LogicalPlan.from_proto(logical_bytes, SessionContext())
+
+
+def test_collect_interrupted():
+    ctx = SessionContext()
+    ctx.from_pydict({"v": list(range(20_000))}, name="t")
+    df = ctx.sql("SELECT sum(l.v * r.v) FROM t AS l CROSS JOIN t AS r")
+
+    timer = threading.Timer(0.2, _thread.interrupt_main)
+    timer.start()
+    start = time.monotonic()
+    with pytest.raises(KeyboardInterrupt):
+        df.collect()
+    timer.join()
+
+    assert time.monotonic() - start < 5
+
+
+def test_plan_tree_walk():
+    ctx = SessionContext(SessionConfig().with_target_partitions(2))
+    left = ctx.from_pydict({"a": [1, 2, 3], "b": [4, 5, 6]})
+    right = ctx.from_pydict({"a": [1, 2], "c": [7, 8]})
+    df = left.join(right, join_keys=(["a"], ["a"]), how="inner")
+
+    def find(plan, predicate):
+        if predicate(plan):
+            return plan
+        for child in plan.inputs():
+            found = find(child, predicate)
+            if found is not None:
+                return found
+        return None
+
+    join = find(df.logical_plan(), lambda p: p.display().startswith("Inner Join"))
+    assert len(join.inputs()) == 2
+    assert "Inner Join" in join.display_indent()
+
+    physical = df.execution_plan()
+    hash_join = find(physical, lambda p: p.display().startswith("HashJoinExec"))
+    assert len(hash_join.children()) == 2
+    assert isinstance(physical.output_partitioning, str)
+
+    repartitioned = left.repartition_by_hash(column("a"), num=3).execution_plan()
+    hashed = find(repartitioned, lambda p: p.output_partitioning.startswith("Hash("))
+    assert hashed is not None
+    assert hashed.partition_count == 3
+
+
+def test_coalesce():
+    config = SessionConfig().with_target_partitions(1).with_batch_size(16)
+    ctx = SessionContext(config)
+    batches = [
+        pa.RecordBatch.from_pydict({"a": list(range(i, i + 100))})
+        for i in range(0, 2000, 100)
+    ]
+    df = ctx.create_dataframe([batches]).filter(column("a") % literal(7) == literal(0))
+
+    def batch_sizes(df):
+        sizes = []
+        stream = df.execute_stream()
+        batch = stream.next()
+        while batch is not None:
+            sizes.append(batch.to_pyarrow().num_rows)
+            batch = stream.next()
+        return sizes
+
+    sparse = batch_sizes(df)
+    coalesced = batch_sizes(df.coalesce(1000))
+    assert sum(sparse) == sum(coalesced) == 286
+    assert len(coalesced) < len(sparse)
+    assert max(coalesced) > max(sparse)
+    expected = df.sort(column("a")).to_pydict()
+    assert df.coalesce(1000).sort(column("a")).to_pydict() == expected
+
+    with pytest.raises(ValueError, match="greater than 0"):
+        df.coalesce(0)
+
+
 def test_repartition(df):
     df.repartition(2)
 
@@ -536,6 +1383,19 @@ def test_except_all():
     assert df_c.collect() == df_a_e_b.collect()
 
 
+def test_set_operations_keep_multiplicities():
+    ctx = SessionContext()
+    left = ctx.from_pydict({"a": [1, 1, 1, 2, None, None], "b": ["x"] * 6})
+    right = ctx.from_pydict({"a": [1, 1, 3, None], "b": ["x"] * 4})
+
+    def values(df):
+        return sorted(df.to_pydict()["a"], key=lambda v: (v is None, v))
+
+    assert values(left.except_all(right)) == [1, 2, None]
+    assert values(left.intersect_all(right)) == [1, 1, None]
+    assert values(right.except_all(left)) == [3]
+
+
 def test_collect_partitioned():
     ctx = SessionContext()
 
@@ -596,6 +1456,40 @@ def test_union_distinct(ctx):
     assert df_c.collect() == df_a_u_b.collect()
 
 
+@pytest.mark.parametrize(
+    "right, except_distinct, union_distinct",
+    [
+        # overlapping inputs
+        ([2, 3, 3, 4], [1], [1, 2, 3, 4]),
+        # disjoint inputs
+        ([7, 8, 8], [1, 2, 3], [1, 2, 3, 7, 8]),
+    ],
+)
+def test_distinct_set_operations(ctx, right, except_distinct, union_distinct):
+    df_a = ctx.from_pydict({"a": [1, 1, 2, 2, 3]})
+    df_b = ctx.from_pydict({"a": right})
+
+    def values(df):
+        return df.sort(column("a").sort()).to_pydict()["a"]
+
+    assert values(df_a.except_distinct(df_b)) == except_distinct
+    assert values(df_a.union_distinct(df_b)) == union_distinct
+
+
+@pytest.mark.parametrize(
+    "operation", ["except_distinct", "union_distinct", "intersect"]
+)
+def test_set_operation_schema_mismatch(ctx, operation):
+    df_a = ctx.from_pydict({"a": [1, 2], "b": [3, 4]})
+    df_b = ctx.from_pydict({"a": ["x", "y"], "b": [3, 4]})
+    df_c = ctx.from_pydict({"a": [1, 2]})
+
+    with pytest.raises(ValueError, match=f"{operation} requires .* the same schema"):
+        getattr(df_a, operation)(df_b)
+    with pytest.raises(ValueError, match=r"\[a: Int64, b: Int64\] and \[a: Int64\]"):
+        getattr(df_a, operation)(df_c)
+
+
 def test_cache(df):
     assert df.cache().collect() == df.collect()
 
@@ -605,6 +1499,25 @@ def test_count(df):
     assert df.count() == 3
 
 
+def test_count_after_limit_and_filter(ctx):
+    df = ctx.from_pydict({"a": list(range(10))})
+
+    assert df.limit(4).count() == 4
+    assert df.limit(20).count() == 10
+    assert df.filter(column("a") > literal(6)).count() == 3
+    assert df.filter(column("a") > literal(2)).limit(5).count() == 5
+
+
+def test_count_does_not_evaluate_projection(ctx):
+    def fail(array):
+        raise RuntimeError("projection was evaluated")
+
+    fail = udf(fail, [pa.int64()], pa.int64(), "immutable")
+    df = ctx.from_pydict({"a": [1, 2, 3]}).select(fail(column("a")))
+
+    assert df.count() == 3
+
+
 def test_to_pandas(df):
     # Skip test if pandas is not installed
     pd = pytest.importorskip("pandas")
@@ -627,6 +1540,25 @@ def test_empty_to_pandas(df):
     assert set(pandas_df.columns) == {"a", "b", "c"}
 
 
+def test_to_pandas_nullable_dtypes():
+    pd = pytest.importorskip("pandas")
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": [1, None, 3]})
+
+    assert df.to_pandas()["a"].dtype == "float64"
+
+    result = df.to_pandas(use_nullable_dtypes=True)["a"]
+    assert result.dtype == pd.Int64Dtype()
+    assert result.isna().tolist() == [False, True, False]
+
+    mapper = {pa.int64(): pd.Int64Dtype()}.get
+    assert df.to_pandas(types_mapper=mapper)["a"].dtype == pd.Int64Dtype()
+    assert df.to_pandas(self_destruct=True)["a"].tolist()[0] == 1
+
+    with pytest.raises(ValueError, match="Only one of types_mapper"):
+        df.to_pandas(types_mapper=mapper, use_nullable_dtypes=True)
+
+
 def test_to_polars(df):
     # Skip test if polars is not installed
     pl = pytest.importorskip("polars")
@@ -657,6 +1589,41 @@ def test_to_arrow_table(df):
     assert set(pyarrow_table.column_names) == {"a", "b", "c"}
 
 
+@pytest.fixture
+def large_types_table():
+    return pa.table(
+        {
+            "s": pa.array(["a", None, "ccc"], type=pa.large_string()),
+            "l": pa.array([[1, 2], [], None], type=pa.large_list(pa.int64())),
+        }
+    )
+
+
+def test_to_arrow_table_large_types(ctx, large_types_table):
+    df = ctx.from_arrow_table(large_types_table)
+
+    table = df.to_arrow_table()
+    assert table.schema == large_types_table.schema
+    assert table == pa.Table.from_batches(df.collect())
+    assert table.to_pydict() == large_types_table.to_pydict()
+
+    # the string data buffer is shared with the source table, not copied
+    source = large_types_table.column("s").chunk(0).buffers()[2]
+    result = table.column("s").chunk(0).buffers()[2]
+    assert result.address == source.address
+
+
+def test_to_polars_large_types(ctx, large_types_table):
+    pl = pytest.importorskip("polars")
+    df = ctx.from_arrow_table(large_types_table)
+
+    polars_df = df.to_polars()
+    assert polars_df.schema == {"s": pl.String, "l": pl.List(pl.Int64)}
+    assert polars_df.to_dict(as_series=False) == (
+        pa.Table.from_batches(df.collect()).to_pydict()
+    )
+
+
 def test_execute_stream(df):
     stream = df.execute_stream()
     assert all(batch is not None for batch in stream)
@@ -746,6 +1713,30 @@ def test_write_parquet(df, tmp_path):
     assert result == expected
 
 
+def test_write_parquet_max_rows_per_file(ctx, tmp_path):
+    batches = [
+        pa.RecordBatch.from_pydict({"a": list(range(i, i + 500))})
+        for i in range(0, 10_000, 500)
+    ]
+    df = ctx.create_dataframe([batches])
+
+    files = df.write_parquet(str(tmp_path), max_rows_per_file=1000)
+
+    assert len(files) >= 10
+    assert files == sorted(str(p) for p in tmp_path.glob("*.parquet"))
+    for file in files:
+        metadata = pq.ParquetFile(file).metadata
+        assert metadata.num_rows <= 1000
+        for i in range(metadata.num_row_groups):
+            assert metadata.row_group(i).num_rows <= 1000
+
+    result = pq.read_table(str(tmp_path)).sort_by("a")
+    assert result.column("a").to_pylist() == list(range(10_000))
+
+    with pytest.raises(ValueError, match="greater than 0"):
+        df.write_parquet(str(tmp_path), max_rows_per_file=0)
+
+
 @pytest.mark.parametrize(
     "compression, compression_level",
     [("gzip", 6), ("brotli", 7), ("zstd", 15)],
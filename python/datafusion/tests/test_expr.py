@@ -15,7 +15,9 @@
 # specific language governing permissions and limitations
 # under the License.
 
-from datafusion import SessionContext
+import pyarrow as pa
+
+from datafusion import ScalarValue, SessionContext, column, literal
 from datafusion.expr import Column, Literal, BinaryExpr, AggregateFunction
 from datafusion.expr import (
     Projection,
@@ -116,3 +118,174 @@ def test_sort(test_ctx):
 
     plan = plan.to_variant()
     assert isinstance(plan, Sort)
+
+
+def test_fill_null():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {"a": [1, None, 3], "b": [10, 20, None], "s": ["x", None, "z"]}
+    )
+
+    result = df.select(
+        column("a").fill_null(0),
+        column("a").fill_null(1.5),
+        column("a").fill_null(column("b")),
+        column("s").fill_null("?"),
+    ).collect()[0]
+
+    assert result.column(0).to_pylist() == [1, 0, 3]
+    assert result.column(1).to_pylist() == [1.0, 1.5, 3.0]
+    assert result.column(2).to_pylist() == [1, 20, 3]
+    assert result.column(3).to_pylist() == ["x", "?", "z"]
+
+    with pytest.raises(Exception, match="Cannot fill nulls of type Utf8"):
+        df.select(column("s").fill_null(0)).collect()
+
+
+def test_get_field_and_index():
+    ctx = SessionContext()
+    batch = pa.RecordBatch.from_pydict(
+        {
+            "s": [{"x": 1, "y": "a"}, {"x": 2, "y": None}],
+            "l": [[10, 20, 30], [40]],
+        }
+    )
+    df = ctx.create_dataframe([[batch]])
+
+    result = df.select(
+        column("s").get_field("x").alias("x"),
+        column("s")["y"].alias("y"),
+        column("l")[0].alias("first"),
+        column("l")[-1].alias("last"),
+        column("l")[2].alias("third"),
+    ).to_pydict()
+    assert result == {
+        "x": [1, 2],
+        "y": ["a", None],
+        "first": [10, 40],
+        "last": [30, 40],
+        "third": [30, None],
+    }
+
+    with pytest.raises(Exception, match="available fields are: x, y"):
+        df.select(column("s").get_field("z")).collect()
+
+
+def test_like_patterns():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"s": ["Apple", "apple pie", "APPLE_1", "banana", None]})
+    s = column("s")
+
+    result = df.select(
+        s.like("app%").alias("like"),
+        s.ilike("app%").alias("ilike"),
+        s.not_ilike("app%").alias("not_ilike"),
+        s.ilike("apple#_%", escape="#").alias("escaped"),
+        s.similar_to("^(a|b)[a-z]+$").alias("similar"),
+        s.similar_to("^apple", case_insensitive=True).alias("similar_ci"),
+    ).to_pydict()
+
+    assert result == {
+        "like": [False, True, False, False, None],
+        "ilike": [True, True, True, False, None],
+        "not_ilike": [False, False, False, True, None],
+        "escaped": [False, False, True, False, None],
+        "similar": [False, False, False, True, None],
+        "similar_ci": [True, True, True, False, None],
+    }
+
+    with pytest.raises(ValueError, match="ends with the escape character"):
+        s.like("abc#", escape="#")
+
+
+def test_is_distinct_from():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": [1, 1, None, None], "b": [1, 2, 1, None]})
+    a, b = column("a"), column("b")
+
+    result = df.select(
+        a.is_distinct_from(b).alias("distinct"),
+        a.is_not_distinct_from(b).alias("not_distinct"),
+        (a == b).alias("eq"),
+        a.is_not_distinct_from(None).alias("is_null"),
+    ).to_pydict()
+
+    assert result == {
+        "distinct": [False, True, True, False],
+        "not_distinct": [True, False, False, True],
+        "eq": [True, False, None, None],
+        "is_null": [False, False, True, True],
+    }
+
+
+def test_cast_to_column_type():
+    ctx = SessionContext()
+    df = ctx.from_arrow_table(
+        pa.table({"small": pa.array([1, 2], type=pa.int16()), "s": ["a", "b"]})
+    )
+    schema = df.schema()
+
+    one = literal(1).cast_to("small", schema)
+    result = df.select((column("small") + one).alias("next"))
+    assert result.schema().field("next").type == pa.int16()
+    assert result.to_pydict() == {"next": [2, 3]}
+
+    as_string = literal(7).cast_to(pa.string(), schema)
+    assert df.select(as_string.alias("v")).schema().field("v").type == pa.string()
+
+    with pytest.raises(ValueError, match="available fields: small, s"):
+        literal(1).cast_to("missing", schema)
+
+
+def test_typed_scalar_values():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": [1, 2]})
+
+    typed_null = ScalarValue.null(pa.int64())
+    assert typed_null.is_null()
+    assert typed_null.type == pa.int64()
+
+    result = df.select(
+        (column("a") + literal(typed_null)).alias("plus_null"),
+        literal(ScalarValue.float32(1.5)).alias("f32"),
+        literal(ScalarValue.from_arrow(pa.array(["x"]))).alias("s"),
+    )
+    schema = result.schema()
+    assert schema.field("plus_null").type == pa.int64()
+    assert schema.field("f32").type == pa.float32()
+    assert result.to_pydict() == {
+        "plus_null": [None, None],
+        "f32": [1.5, 1.5],
+        "s": ["x", "x"],
+    }
+
+    # accepted wherever a literal is
+    filled = df.select(column("a").fill_null(ScalarValue.int64(0)).alias("a"))
+    assert filled.to_pydict() == {"a": [1, 2]}
+
+    with pytest.raises(ValueError, match="one element"):
+        ScalarValue.from_arrow(pa.array([1, 2]))
+
+
+def test_expr_children_round_trip():
+    expr = (column("a") + literal(1)) > column("b")
+    assert expr.variant_name() == "BinaryExpr"
+
+    left, right = expr.children()
+    assert left.variant_name() == "BinaryExpr"
+    assert right.to_variant().name() == "b"
+
+    a, one = left.children()
+    assert a.to_variant().name() == "a"
+    assert one.python_value().as_py() == 1
+    assert one.children() == []
+
+    rebuilt = expr.with_children(expr.children())
+    assert rebuilt.canonical_name() == expr.canonical_name()
+
+    rewritten = expr.with_children([left.with_children([a, literal(2)]), right])
+    expected = (column("a") + literal(2)) > column("b")
+    assert rewritten.canonical_name() == expected.canonical_name()
+
+    with pytest.raises(ValueError, match="BinaryExpr has 2 children, got 1"):
+        expr.with_children([left])
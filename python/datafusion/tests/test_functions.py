@@ -18,12 +18,14 @@ import math
 
 import numpy as np
 import pyarrow as pa
+import pyarrow.compute as pc
 import pytest
-from datetime import datetime
+from datetime import date, datetime, timedelta, timezone
+from decimal import Decimal, InvalidOperation
 
 from datafusion import SessionContext, column
 from datafusion import functions as f
-from datafusion import literal
+from datafusion import literal, udf
 
 np.seterr(invalid="ignore")
 
@@ -76,6 +78,147 @@ def test_named_struct(df):
     assert str(df) == expected
 
 
+def test_map_from_entries():
+    ctx = SessionContext()
+    entry_type = pa.struct([("k", pa.string()), ("v", pa.int64())])
+    entries = pa.array(
+        [[{"k": "x", "v": 1}, {"k": "y", "v": None}], [], None],
+        type=pa.list_(entry_type),
+    )
+    df = ctx.from_arrow_table(pa.table({"entries": entries}))
+
+    result = df.select(f.map_from_entries(column("entries"))).collect()[0]
+    assert result.column(0).to_pylist() == [[("x", 1), ("y", None)], [], None]
+
+
+def test_map_from_arrays():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"keys": [["a", "b"], ["c"]], "values": [[1, 2], [3]]})
+
+    result = df.select(f.map_from_arrays(column("keys"), column("values"))).collect()
+    assert result[0].column(0).to_pylist() == [[("a", 1), ("b", 2)], [("c", 3)]]
+
+
+def test_map_extract():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {"keys": [["a", "b"], ["c"], None], "values": [[1, 2], [3], None]}
+    )
+    df = df.select(f.map(column("keys"), column("values")).alias("m"))
+
+    result = df.select(
+        f.map_extract(column("m"), literal("b")),
+        f.map_extract(column("m"), literal("c")),
+    ).collect()[0]
+    assert result.column(0).to_pylist() == [2, None, None]
+    assert result.column(1).to_pylist() == [None, 3, None]
+
+
+@pytest.mark.parametrize(
+    "keys, values, message",
+    [
+        ([["a", None]], [[1, 2]], "null keys"),
+        ([["a", "a"]], [[1, 2]], "duplicate key"),
+        ([["a", "b"]], [[1]], "as many keys as values"),
+    ],
+)
+def test_map_from_arrays_invalid_keys(keys, values, message):
+    ctx = SessionContext()
+    df = ctx.from_pydict({"keys": keys, "values": values})
+
+    df = df.select(f.map_from_arrays(column("keys"), column("values")))
+    with pytest.raises(Exception, match=message):
+        df.collect()
+
+
+def test_zip_with():
+    ctx = SessionContext()
+    add = udf(
+        lambda a, b: pc.add(a, b),
+        [pa.int64(), pa.int64()],
+        pa.int64(),
+        "immutable",
+        name="add",
+    )
+    ctx.register_udf(add)
+    df = ctx.from_pydict({"a": [[1, 2, 3], [10], None], "b": [[10, 20], [1, 2], [1]]})
+
+    result = df.select(f.zip_with(column("a"), column("b"), "add", ctx)).collect()[0]
+
+    assert result.column(0).type == pa.list_(pa.int64())
+    assert result.column(0).to_pylist() == [[11, 22, None], [11, None], None]
+
+    with pytest.raises(Exception, match="missing"):
+        f.zip_with(column("a"), column("b"), "missing", ctx)
+
+
+def test_array_filter():
+    ctx = SessionContext()
+    is_positive = udf(
+        lambda a: pc.greater(a, 0),
+        [pa.int64()],
+        pa.bool_(),
+        "immutable",
+        name="is_positive",
+    )
+    ctx.register_udf(is_positive)
+    df = ctx.from_pydict({"a": [[3, -1, None, 2], [-5], [], None]})
+
+    result = df.select(f.array_filter(column("a"), "is_positive", ctx)).collect()[0]
+
+    assert result.column(0).type == pa.list_(pa.int64())
+    assert result.column(0).to_pylist() == [[3, 2], [], [], None]
+
+def test_array_transform():
+    ctx = SessionContext()
+    double = udf(
+        lambda a: pc.multiply(a, 2),
+        [pa.int64()],
+        pa.int64(),
+        "immutable",
+        name="double",
+    )
+    ctx.register_udf(double)
+    df = ctx.from_pydict({"a": [[1, None, 3], [], None]})
+
+    result = df.select(f.array_transform(column("a"), "double", ctx)).collect()[0]
+
+    assert result.column(0).type == pa.list_(pa.int64())
+    assert result.column(0).to_pylist() == [[2, None, 6], [], None]
+
+def test_nullif_nan_and_inf():
+    ctx = SessionContext()
+    nan, inf = float("nan"), float("inf")
+    df = ctx.from_pydict({"a": [1.5, nan, inf, -inf, None], "i": [1, 2, 3, 4, 5]})
+
+    result = df.select(
+        f.nullif_nan(column("a")).alias("no_nan"),
+        f.nullif_inf(column("a")).alias("no_inf"),
+        f.nullif_inf(f.nullif_nan(column("a"))).alias("finite"),
+    ).to_pydict()
+
+    assert result["no_nan"] == [1.5, None, inf, -inf, None]
+    assert result["no_inf"][0] == 1.5
+    assert math.isnan(result["no_inf"][1])
+    assert result["no_inf"][2:] == [None, None, None]
+    assert result["finite"] == [1.5, None, None, None, None]
+
+    with pytest.raises(Exception, match="nullif_nan expects a float column"):
+        df.select(f.nullif_nan(column("i"))).collect()
+
+def test_nvl2():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": [1, None, 3], "b": [10, 20, 30], "s": ["x", "y", "z"]})
+
+    result = df.select(
+        f.nvl2(column("a"), column("b"), literal(0.5)).alias("nvl2"),
+        f.nvl2(column("a"), literal("set"), literal("missing")).alias("label"),
+    ).to_pydict()
+    assert result == {"nvl2": [10.0, 0.5, 30.0], "label": ["set", "missing", "set"]}
+
+    with pytest.raises(Exception, match="nvl2 cannot combine arguments of types Int64"):
+        df.select(f.nvl2(column("a"), column("b"), f.make_array(column("b")))).collect()
+
 def test_literal(df):
     df = df.select(
         literal(1),
@@ -514,6 +657,42 @@ def test_array_functions(stmt, py_expr):
         )
 
 
+def test_array_has_null_element():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"arr": [[1, None], [1, 2], None]})
+    arr = column("arr")
+    null = literal(pa.scalar(None, type=pa.int64()))
+
+    df = df.select(
+        f.array_has(arr, null),
+        f.array_has(arr, null, null_matches=True),
+        f.array_has_all(arr, f.make_array(literal(1), null)),
+        f.array_has_all(arr, f.make_array(literal(1), null), null_matches=True),
+        f.array_has_any(arr, f.make_array(literal(3), null)),
+        f.array_has_any(arr, f.make_array(literal(1), null)),
+        f.array_has_any(arr, f.make_array(literal(3), null), null_matches=True),
+    )
+    result = df.collect()[0]
+
+    assert result.column(0).to_pylist() == [None, None, None]
+    assert result.column(1).to_pylist() == [True, False, None]
+    assert result.column(2).to_pylist() == [None, None, None]
+    assert result.column(3).to_pylist() == [True, False, None]
+    assert result.column(4).to_pylist() == [None, None, None]
+    assert result.column(5).to_pylist() == [True, True, None]
+    assert result.column(6).to_pylist() == [True, False, None]
+
+
+def test_array_has_null_matching_large_list():
+    ctx = SessionContext()
+    arr = pa.array([[1, None], [1, 2]], type=pa.large_list(pa.int64()))
+    df = ctx.from_arrow_table(pa.table({"arr": arr}))
+    null = literal(pa.scalar(None, type=pa.int64()))
+
+    result = df.select(f.array_has(column("arr"), null, null_matches=True)).collect()
+    assert result[0].column(0).to_pylist() == [True, False]
+
+
 def test_array_function_flatten():
     data = [[1.0, 2.0, 3.0, 3.0], [4.0, 5.0, 3.0], [6.0]]
     ctx = SessionContext()
@@ -529,6 +708,40 @@ def test_array_function_flatten():
         )
 
 
+def test_array_flatten_deep():
+    data = [
+        [[[1, 2], [3]], [[4, None]]],
+        [[[5]], None, [None, [6, 7]]],
+        None,
+        [],
+    ]
+    ctx = SessionContext()
+    df = ctx.from_pydict({"arr": data})
+
+    result = df.select(f.array_flatten_deep(column("arr"))).collect()[0]
+
+    assert result.column(0).type == pa.list_(pa.int64())
+    # null lists below the top level contribute no elements, null elements are kept
+    assert result.column(0).to_pylist() == [
+        [1, 2, 3, 4, None],
+        [5, 6, 7],
+        None,
+        [],
+    ]
+
+
+def test_array_flatten_alias():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"arr": [[[1, 2], [3]], [[4]]]})
+
+    result = df.select(
+        f.array_flatten(column("arr")), f.flatten(column("arr"))
+    ).collect()[0]
+
+    assert result.column(0).to_pylist() == [[1, 2, 3], [4]]
+    assert result.column(0) == result.column(1)
+
+
 @pytest.mark.parametrize(
     ("stmt", "py_expr"),
     [
@@ -634,6 +847,116 @@ def test_string_functions(df):
     assert result.column(28) == pa.array([True, False, False])
 
 
+def test_coalesce_flattens_nested_calls():
+    ctx = SessionContext()
+    batch = pa.RecordBatch.from_arrays(
+        [
+            pa.array([None, None, 3, None]),
+            pa.array([None, 2, 30, None]),
+            pa.array([1, 20, 300, None]),
+            pa.array([10, 200, 3000, 4]),
+        ],
+        names=["a", "b", "c", "d"],
+    )
+    df = ctx.create_dataframe([[batch]])
+
+    nested = f.coalesce(
+        f.coalesce(column("a"), f.coalesce(column("b"), column("c"))), column("d")
+    )
+    assert repr(nested) == "Expr(coalesce(a, b, c, d))"
+
+    flat = f.coalesce(column("a"), column("b"), column("c"), column("d"))
+    result = df.select(nested, flat).collect()[0]
+    assert result.column(0) == pa.array([1, 2, 3, 4])
+    assert result.column(0) == result.column(1)
+
+
+def test_regexp_replace_occurrence():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": ["aaa", "abcabc", None]})
+    pattern = literal("[ab]")
+    df = df.select(
+        f.regexp_replace(column("a"), pattern, literal("X"), occurrence=literal(2)),
+        f.regexp_replace(column("a"), pattern, literal("X"), occurrence=literal(0)),
+        f.regexp_replace(
+            column("a"),
+            pattern,
+            literal("X"),
+            position=literal(2),
+            occurrence=literal(1),
+        ),
+        f.regexp_replace(
+            column("a"), pattern, literal("X"), literal("g"), position=literal(3)
+        ),
+        f.regexp_replace(column("a"), pattern, literal("X"), position=literal(3)),
+        f.regexp_replace(
+            column("a"), literal("(b)(c)"), literal("\\2\\1"), occurrence=literal(2)
+        ),
+    )
+    result = df.collect()[0]
+    assert result.column(0) == pa.array(["aXa", "aXcabc", None])
+    assert result.column(1) == pa.array(["XXX", "XXcXXc", None])
+    assert result.column(2) == pa.array(["aXa", "aXcabc", None])
+    assert result.column(3) == pa.array(["aaX", "abcXXc", None])
+    assert result.column(4) == pa.array(["aaX", "abcXbc", None])
+    assert result.column(5) == pa.array(["aaa", "abcacb", None])
+
+
+def test_regexp_replace_occurrence_large_string():
+    ctx = SessionContext()
+    df = ctx.from_arrow_table(
+        pa.table({"a": pa.array(["abcabc", None], type=pa.large_string())})
+    )
+    expr = f.regexp_replace(
+        column("a"), literal("b"), literal("X"), occurrence=literal(pa.scalar(2, pa.int32()))
+    )
+    assert "regexp_replace_nth" in str(expr)
+
+    result = df.select(expr).collect()[0]
+    assert result.column(0).to_pylist() == ["abcaXc", None]
+
+
+def test_regexp_replace_occurrence_conflicts_with_global_flag():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": ["aaa"]})
+    df = df.select(
+        f.regexp_replace(
+            column("a"), literal("a"), literal("X"), literal("g"), occurrence=literal(2)
+        )
+    )
+    with pytest.raises(Exception, match="'g' flag"):
+        df.collect()
+
+
+def test_regexp_count_and_instr():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": ["abcABCabc", "aaaa", None]})
+    df = df.select(
+        f.regexp_count(column("a"), literal("abc")),
+        f.regexp_count(column("a"), literal("abc"), flags=literal("i")),
+        f.regexp_count(column("a"), literal("abc"), start=literal(2)),
+        f.regexp_count(column("a"), literal("aa")),
+        f.regexp_instr(column("a"), literal("abc"), occurrence=literal(2)),
+        f.regexp_instr(column("a"), literal("b"), literal(3), flags=literal("i")),
+    )
+    result = df.collect()[0]
+    assert result.column(0) == pa.array([2, 0, None])
+    assert result.column(1) == pa.array([3, 0, None])
+    assert result.column(2) == pa.array([1, 0, None])
+    # matches do not overlap
+    assert result.column(3) == pa.array([0, 2, None])
+    assert result.column(4) == pa.array([7, 0, None])
+    assert result.column(5) == pa.array([5, 0, None])
+
+
+def test_regexp_count_empty_pattern():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": ["abc"]})
+    df = df.select(f.regexp_count(column("a"), literal("")))
+    with pytest.raises(Exception, match="pattern must not be empty"):
+        df.collect()
+
+
 def test_hash_functions(df):
     exprs = [
         f.digest(column("a"), literal(m))
@@ -740,6 +1063,28 @@ def test_hash_functions(df):
     assert result.column(10) == result.column(4)  # SHA-512
 
 
+def test_to_unixtime():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {
+            "t": pa.array(
+                [datetime(2023, 1, 10, 20, 52, 54, 999999), None],
+                type=pa.timestamp("us"),
+            ),
+            "s": [1673383974, None],
+        }
+    )
+    result = df.select(
+        f.to_unixtime(column("t")).alias("t"),
+        f.to_unixtime(f.from_unixtime(column("s"))).alias("round_trip"),
+    )
+    assert result.schema().field("t").type == pa.int64()
+    assert result.to_pydict() == {
+        "t": [1673383974, None],
+        "round_trip": [1673383974, None],
+    }
+
+
 def test_temporal_functions(df):
     df = df.select(
         f.date_part(literal("month"), column("d")),
@@ -795,6 +1140,204 @@ def test_temporal_functions(df):
     )
 
 
+@pytest.mark.parametrize(
+    "unit, arrow_unit",
+    [("seconds", "s"), ("millis", "ms"), ("micros", "us"), ("nanos", "ns")],
+)
+def test_to_timestamp_unit(unit, arrow_unit):
+    ctx = SessionContext()
+    # 10**11 seconds is far beyond the range of nanosecond timestamps
+    df = ctx.from_pydict({"a": [0, 1_700_000_000, 10**11]})
+    df = df.select(
+        f.to_timestamp(column("a"), unit=unit),
+        f.to_timestamp(literal("2023-09-07 05:06:14.523952"), unit=unit),
+    )
+    result = df.collect()[0]
+
+    assert result.column(0).type == pa.timestamp(arrow_unit)
+    assert result.column(0).cast(pa.int64()) == pa.array([0, 1_700_000_000, 10**11])
+    assert result.column(1) == pa.array(
+        [datetime(2023, 9, 7, 5, 6, 14, 523952)] * 3, type=pa.timestamp("ns")
+    )
+
+
+def test_to_timestamp_defaults_to_seconds():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": [1_700_000_000]})
+    result = df.select(f.to_timestamp(column("a"))).collect()[0]
+    assert result.column(0) == pa.array(
+        [datetime(2023, 11, 14, 22, 13, 20)], type=pa.timestamp("ns")
+    )
+
+    with pytest.raises(ValueError, match="Unknown unit 'days'"):
+        f.to_timestamp(column("a"), unit="days")
+
+
+def test_to_timestamp_nanos():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": ["2023-09-07 05:06:14.523952123"], "b": [1]})
+    result = df.select(
+        f.to_timestamp_nanos(column("a")), f.to_timestamp_nanos(column("b"))
+    ).collect()[0]
+
+    # digits beyond microsecond precision are kept
+    assert result.column(0).type == pa.timestamp("ns")
+    assert result.column(0).cast(pa.int64()) == pa.array([1694063174523952123])
+    assert result.column(1).type == pa.timestamp("ns")
+    assert result.column(1).cast(pa.int64()) == pa.array([1])
+
+
+def test_to_timestamp_tz():
+    ctx = SessionContext()
+    # New York moves from UTC-5 to UTC-4 at 2024-03-10 02:00 local time
+    naive = pa.array(
+        [datetime(2024, 3, 10, 1, 30), datetime(2024, 3, 10, 3, 30)],
+        type=pa.timestamp("us"),
+    )
+    df = ctx.from_arrow_table(pa.table({"a": naive}))
+
+    result = df.select(f.to_timestamp_tz(column("a"), "America/New_York"))
+    result = result.collect()[0].column(0)
+
+    assert result.type == pa.timestamp("ns", tz="America/New_York")
+    # the stored values are UTC instants
+    expected = pa.array(
+        [datetime(2024, 3, 10, 6, 30), datetime(2024, 3, 10, 7, 30)],
+        type=pa.timestamp("ns"),
+    )
+    assert result.cast(pa.int64()) == expected.cast(pa.int64())
+
+    with pytest.raises(ValueError, match="Unknown time zone 'Mars/Olympus'"):
+        f.to_timestamp_tz(column("a"), "Mars/Olympus")
+
+
+def test_lit_decimal():
+    ctx = SessionContext()
+    df = ctx.from_arrow_table(
+        pa.table(
+            {"d": pa.array([Decimal("1.10"), Decimal("2.50")], pa.decimal128(10, 2))}
+        )
+    )
+
+    result = df.select(
+        f.lit_decimal("1.25", 10, 2),
+        column("d") + f.lit_decimal(1.25, 10, 2),
+        column("d") * f.lit_decimal(2, 3, 0),
+    ).collect()[0]
+
+    assert result.column(0).type == pa.decimal128(10, 2)
+    assert result.column(1).type == pa.decimal128(11, 2)
+    assert result.column(1).to_pylist() == [Decimal("2.35"), Decimal("3.75")]
+    assert result.column(2).type == pa.decimal128(14, 2)
+    assert result.column(2).to_pylist() == [Decimal("2.20"), Decimal("5.00")]
+
+    with pytest.raises(ValueError, match=r"does not fit in Decimal128\(10, 2\)"):
+        f.lit_decimal("1.234", 10, 2)
+    with pytest.raises(ValueError, match=r"does not fit in Decimal128\(3, 0\)"):
+        f.lit_decimal(1000, 3, 0)
+    with pytest.raises(InvalidOperation):
+        f.lit_decimal("not a number", 10, 2)
+
+
+def test_lit_timestamp_and_interval():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": [1]})
+
+    result = df.select(
+        f.lit_timestamp(datetime(2024, 1, 31, 12)),
+        f.lit_timestamp(0, unit="s", tz="UTC"),
+        f.lit_interval(months=1, days=2),
+        f.lit_timestamp(datetime(2024, 1, 31, 12)) + f.lit_interval(days=1),
+    ).collect()[0]
+
+    assert result.column(0).type == pa.timestamp("us")
+    assert result.column(0)[0].as_py() == datetime(2024, 1, 31, 12)
+    assert result.column(1).type == pa.timestamp("s", tz="UTC")
+    assert result.column(1).cast(pa.int64())[0].as_py() == 0
+    assert result.column(2).type == pa.month_day_nano_interval()
+    assert result.column(3)[0].as_py() == datetime(2024, 2, 1, 12)
+
+
+def test_date_add_and_sub():
+    ctx = SessionContext()
+    df = ctx.from_pydict(
+        {"d": [date(2021, 1, 31), date(2020, 1, 31), date(2021, 3, 31)]}
+    )
+
+    result = df.select(
+        f.date_add(column("d"), f.interval("1 month")).alias("add"),
+        f.date_sub(column("d"), f.interval("1 month")).alias("sub"),
+        f.date_add(column("d"), f.interval("1 month 1 day")).alias("add_days"),
+    ).to_pydict()
+
+    assert result == {
+        "add": [date(2021, 2, 28), date(2020, 2, 29), date(2021, 4, 30)],
+        "sub": [date(2020, 12, 31), date(2019, 12, 31), date(2021, 2, 28)],
+        "add_days": [date(2021, 3, 1), date(2020, 3, 1), date(2021, 5, 1)],
+    }
+
+    with pytest.raises(ValueError, match="Invalid interval 'later'"):
+        f.interval("later")
+
+@pytest.mark.parametrize(
+    "position, length, expected",
+    [
+        (3, None, "phabet"),
+        (3, 2, "ph"),
+        (0, 3, "al"),
+        (-2, 5, "al"),
+        (-2, 3, ""),
+        (-5, None, "alphabet"),
+        (7, 10, "et"),
+        (10, 2, ""),
+    ],
+)
+def test_substr(position, length, expected):
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": ["alphabet", None]})
+
+    length = None if length is None else literal(length)
+    result = df.select(f.substr(column("a"), literal(position), length))
+
+    assert result.collect()[0].column(0).to_pylist() == [expected, None]
+
+
+def test_substr_negative_length():
+    ctx = SessionContext()
+    df = ctx.from_pydict({"a": ["alphabet"]})
+
+    with pytest.raises(Exception, match="negative substring length"):
+        df.select(f.substr(column("a"), literal(1), literal(-1))).collect()
+
+
+def test_session_time_zone():
+    ctx = SessionContext()
+    ctx.set_config("datafusion.execution.time_zone", "+14:00")
+
+    def local_date():
+        return (datetime.now(timezone.utc) + timedelta(hours=14)).date()
+
+    before = local_date()
+    sql = ctx.sql("SELECT now() AS n, current_date() AS d").collect()[0]
+    df = ctx.from_pydict({"a": [1]})
+    api = df.select(f.now(ctx), f.current_date(ctx), f.current_time(ctx))
+    api = api.collect()[0]
+    after = local_date()
+
+    assert sql.column(0).type == pa.timestamp("ns", tz="+14:00")
+    assert sql.column(1)[0].as_py() in (before, after)
+    assert api.column(0).type == pa.timestamp("ns", tz="+14:00")
+    assert api.column(1)[0].as_py() in (before, after)
+    assert api.column(2).type == pa.time64("ns")
+
+    # without a session the functions work in UTC
+    before = datetime.now(timezone.utc).date()
+    utc = df.select(f.now(), f.current_date()).collect()[0]
+    after = datetime.now(timezone.utc).date()
+    assert utc.column(0).type == pa.timestamp("ns", tz="+00:00")
+    assert utc.column(1)[0].as_py() in (before, after)
+
+
 def test_case(df):
     df = df.select(
         f.case(column("b")).when(literal(4), literal(10)).otherwise(literal(8)),
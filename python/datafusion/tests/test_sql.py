@@ -16,10 +16,12 @@
 # under the License.
 import gzip
 import os
+import re
 
 import numpy as np
 import pyarrow as pa
 import pyarrow.dataset as ds
+import pyarrow.parquet as pq
 import pytest
 from datafusion.object_store import LocalFileSystem
 
@@ -97,6 +99,32 @@ def test_register_csv(ctx, tmp_path):
         ctx.register_csv("csv4", path, file_compression_type="rar")
 
 
+def test_register_csv_column_types(ctx, tmp_path):
+    path = tmp_path / "zips.csv"
+    path.write_text("zip,population\n02134,100\n10001,200\n")
+
+    ctx.register_csv("zips", path, column_types={"zip": pa.string()})
+    result = ctx.table("zips")
+    assert result.schema().field("zip").type == pa.string()
+    assert result.schema().field("population").type == pa.int64()
+    assert result.to_pydict() == {"zip": ["02134", "10001"], "population": [100, 200]}
+
+    ctx.register_csv("bad", path, column_types={"zip": pa.date32()})
+    with pytest.raises(Exception, match="02134"):
+        ctx.table("bad").collect()
+
+    with pytest.raises(ValueError, match="Column 'code' in column_types"):
+        ctx.register_csv("zips2", path, column_types={"code": pa.string()})
+
+    with pytest.raises(ValueError, match="Only one of schema and column_types"):
+        ctx.register_csv(
+            "zips3",
+            path,
+            schema=pa.schema([("zip", pa.string())]),
+            column_types={"zip": pa.string()},
+        )
+
+
 def test_register_parquet(ctx, tmp_path):
     path = helpers.write_parquet(tmp_path / "a.parquet", helpers.data())
     ctx.register_parquet("t", path)
@@ -107,6 +135,33 @@ def test_register_parquet(ctx, tmp_path):
     assert result.to_pydict() == {"cnt": [100]}
 
 
+@pytest.mark.parametrize("enable_page_index", [True, False])
+def test_register_parquet_page_index(ctx, tmp_path, capsys, enable_page_index):
+    # a single row group of many small pages, sorted so that page statistics are
+    # selective
+    path = tmp_path / "paged.parquet"
+    table = pa.table({"a": pa.array(range(10_000), type=pa.int64())})
+    pq.write_table(
+        table,
+        path,
+        row_group_size=10_000,
+        data_page_size=1024,
+        write_batch_size=100,
+        write_page_index=True,
+    )
+    ctx.register_parquet("t", str(path), enable_page_index=enable_page_index)
+
+    df = ctx.sql("SELECT a FROM t WHERE a = 4321")
+    assert df.to_pydict() == {"a": [4321]}
+
+    df.explain(analyze=True)
+    filtered = re.search(r"page_index_rows_filtered=(\d+)", capsys.readouterr().out)
+    if enable_page_index:
+        assert int(filtered.group(1)) > 9_000
+    else:
+        assert filtered is None or int(filtered.group(1)) == 0
+
+
 def test_register_parquet_partitioned(ctx, tmp_path):
     dir_root = tmp_path / "dataset_parquet_partitioned"
     dir_root.mkdir(exist_ok=False)
@@ -430,3 +485,60 @@ def test_register_listing_table(ctx, tmp_path, pass_schema, file_sort_order):
 
     rd = result.to_pydict()
     assert dict(zip(rd["grp"], rd["count"])) == {"a": 3, "b": 2}
+
+
+def test_sql_params(ctx):
+    ctx.from_pydict(
+        {"id": pa.array([1, 5, 7], type=pa.int32()), "name": ["a", "b", "c"]},
+        name="t",
+    )
+
+    df = ctx.sql("SELECT name FROM t WHERE id = $1", params=[5])
+    assert df.to_pydict() == {"name": ["b"]}
+
+    df = ctx.sql("SELECT name FROM t WHERE id = $1", params=[pa.scalar(7, pa.int8())])
+    assert df.to_pydict() == {"name": ["c"]}
+
+    df = ctx.sql(
+        "SELECT name FROM t WHERE id > $low AND name <> $skip",
+        params={"low": 1, "skip": "c"},
+    )
+    assert df.to_pydict() == {"name": ["b"]}
+
+    with pytest.raises(ValueError, match=r"No value given for parameter \$2"):
+        ctx.sql("SELECT name FROM t WHERE id = $1 OR id = $2", params=[5])
+
+
+def test_register_listing_table_skips_marker_files(ctx, tmp_path):
+    table = pa.table({"a": [1, 2, 3]})
+    pa.parquet.write_table(table.slice(0, 2), tmp_path / "part-0.parquet")
+    pa.parquet.write_table(table.slice(2, 1), tmp_path / "part-1.parquet")
+    (tmp_path / "_SUCCESS").touch()
+    (tmp_path / "empty.parquet").touch()
+    (tmp_path / ".part-0.parquet").write_bytes(b"not parquet")
+    (tmp_path / "_temporary.parquet").write_bytes(b"not parquet")
+
+    ctx.register_listing_table("t", str(tmp_path), format="parquet")
+    result = ctx.sql("SELECT count(*) AS n, sum(a) AS s FROM t").to_pydict()
+    assert result == {"n": [3], "s": [6]}
+
+    csv_dir = tmp_path / "csv"
+    csv_dir.mkdir()
+    (csv_dir / "data.csv").write_text("a,b\n1,x\n2,y\n")
+    (csv_dir / "_SUCCESS").touch()
+    ctx.register_listing_table("c", str(csv_dir), file_extension=".csv", format="csv")
+    assert ctx.table("c").count() == 2
+
+    with pytest.raises(ValueError, match="Unknown file format 'orc'"):
+        ctx.register_listing_table("o", str(tmp_path), format="orc")
+
+
+def test_register_listing_table_insert(ctx, tmp_path):
+    pa.parquet.write_table(pa.table({"a": [1, 2]}), tmp_path / "part-0.parquet")
+    (tmp_path / "_SUCCESS").touch()
+    ctx.register_listing_table("t", str(tmp_path), format="parquet")
+
+    ctx.sql("INSERT INTO t VALUES (3)").collect()
+
+    result = ctx.sql("SELECT count(*) AS n, sum(a) AS s FROM t").to_pydict()
+    assert result == {"n": [3], "s": [6]}
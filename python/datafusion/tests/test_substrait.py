@@ -49,3 +49,22 @@ def test_substrait_serialization(ctx):
     df = ctx.create_dataframe_from_logical_plan(logical_plan)
 
     substrait_plan = ss.substrait.producer.to_substrait_plan(df.logical_plan(), ctx)
+
+
+def test_substrait_context_round_trip(ctx):
+    batch = pa.RecordBatch.from_arrays(
+        [pa.array([1, 2, 3, 4]), pa.array(["x", "y", "x", "y"])],
+        names=["a", "b"],
+    )
+
+    ctx.register_record_batches("t", [[batch]])
+
+    df = ctx.sql("SELECT b, SUM(a) AS total FROM t WHERE a > 1 GROUP BY b ORDER BY b")
+
+    substrait_bytes = ctx.substrait_producer(df)
+    assert isinstance(substrait_bytes, bytes)
+
+    round_trip = ctx.substrait_consumer(substrait_bytes)
+
+    assert round_trip.to_pydict() == df.to_pydict()
+    assert round_trip.to_pydict() == {"b": ["x", "y"], "total": [3, 6]}
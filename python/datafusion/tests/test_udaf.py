@@ -15,6 +15,7 @@
 # specific language governing permissions and limitations
 # under the License.
 
+import math
 from typing import List
 
 import pyarrow as pa
@@ -49,6 +50,58 @@ class Summarize(Accumulator):
         return self._sum
 
 
+class GeometricMean(Accumulator):
+    """
+    Accumulator with a state made of several fields.
+    """
+
+    def __init__(self):
+        self._log_sum = 0.0
+        self._count = 0
+
+    def state(self) -> List[pa.Scalar]:
+        return [pa.scalar(self._log_sum), pa.scalar(self._count)]
+
+    def update(self, values: pa.Array) -> None:
+        self._log_sum += pc.sum(pc.ln(values)).as_py() or 0.0
+        self._count += len(values) - values.null_count
+
+    def merge(self, log_sums: pa.Array, counts: pa.Array) -> None:
+        self._log_sum += pc.sum(log_sums).as_py() or 0.0
+        self._count += pc.sum(counts).as_py() or 0
+
+    def evaluate(self) -> pa.Scalar:
+        if self._count == 0:
+            return pa.scalar(None, type=pa.float64())
+        return pa.scalar(math.exp(self._log_sum / self._count))
+
+
+class Mean(Accumulator):
+    """
+    Accumulator keeping a (sum, count) state.
+    """
+
+    def __init__(self):
+        self._sum = 0.0
+        self._count = 0
+
+    def state(self) -> List[pa.Scalar]:
+        return [pa.scalar(self._sum), pa.scalar(self._count)]
+
+    def update(self, values: pa.Array) -> None:
+        self._sum += pc.sum(values).as_py() or 0.0
+        self._count += len(values) - values.null_count
+
+    def merge(self, sums: pa.Array, counts: pa.Array) -> None:
+        self._sum += pc.sum(sums).as_py() or 0.0
+        self._count += pc.sum(counts).as_py() or 0
+
+    def evaluate(self) -> pa.Scalar:
+        if self._count == 0:
+            return pa.scalar(None, type=pa.float64())
+        return pa.scalar(self._sum / self._count)
+
+
 class NotSubclassOfAccumulator:
     pass
 
@@ -134,3 +187,65 @@ def test_group_by(df):
     arrays = [batch.column(1) for batch in batches]
     joined = pa.concat_arrays(arrays)
     assert joined == pa.array([1.0 + 2.0, 3.0])
+
+
+def test_register_udaf_with_multiple_states():
+    ctx = SessionContext()
+
+    # two partitions so that partial states are merged
+    batch1 = pa.RecordBatch.from_arrays(
+        [pa.array([1.0, 2.0]), pa.array(["x", "y"])],
+        names=["a", "b"],
+    )
+    batch2 = pa.RecordBatch.from_arrays(
+        [pa.array([4.0, 8.0]), pa.array(["x", "y"])],
+        names=["a", "b"],
+    )
+    ctx.register_record_batches("t", [[batch1], [batch2]])
+
+    geo_mean = udaf(
+        GeometricMean,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64(), pa.int64()],
+        volatility="immutable",
+        name="geo_mean",
+    )
+    ctx.register_udaf(geo_mean)
+
+    result = ctx.sql("SELECT b, geo_mean(a) AS g FROM t GROUP BY b ORDER BY b")
+    result = result.to_pydict()
+
+    assert result["b"] == ["x", "y"]
+    assert result["g"] == pytest.approx([2.0, 4.0])
+
+
+def test_register_udaf_with_state_schema():
+    ctx = SessionContext()
+
+    # several partitions so that partial states are merged
+    batches = [
+        [pa.RecordBatch.from_pydict({"a": [1.0, 2.0, None], "b": ["x", "y", "x"]})],
+        [pa.RecordBatch.from_pydict({"a": [3.0, 6.0], "b": ["x", "y"]})],
+        [pa.RecordBatch.from_pydict({"a": [5.0], "b": ["x"]})],
+    ]
+    ctx.register_record_batches("t", batches)
+
+    # the count is carried as int32 even though `state` returns int64 scalars,
+    # and the result is float32 even though `evaluate` returns a float64
+    mean = udaf(
+        Mean,
+        pa.float64(),
+        pa.float32(),
+        pa.schema([("sum", pa.float64()), ("count", pa.int32())]),
+        volatility="immutable",
+        name="my_mean",
+    )
+    ctx.register_udaf(mean)
+
+    result = ctx.sql("SELECT b, my_mean(a) AS m FROM t GROUP BY b ORDER BY b")
+    result = result.to_arrow_table()
+
+    assert result.schema.field("m").type == pa.float32()
+    assert result.column("b").to_pylist() == ["x", "y"]
+    assert result.column("m").to_pylist() == pytest.approx([3.0, 4.0])
@@ -0,0 +1,132 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+from typing import List
+
+import pyarrow as pa
+import pyarrow.compute as pc
+import pytest
+
+from datafusion import SessionContext, WindowEvaluator, column, udwf
+from datafusion import functions as f
+
+
+class TrailingAverage(WindowEvaluator):
+    """
+    Averages each row with the row preceding it in the partition.
+    """
+
+    def evaluate_all(self, values: List[pa.Array], num_rows: int) -> pa.Array:
+        values = values[0].to_pylist()
+        result = []
+        for idx in range(num_rows):
+            window = values[max(idx - 1, 0) : idx + 1]
+            result.append(sum(window) / len(window))
+        return pa.array(result, type=pa.float64())
+
+
+class WrongLength(WindowEvaluator):
+    def evaluate_all(self, values: List[pa.Array], num_rows: int) -> pa.Array:
+        return pc.cast(values[0], pa.float64())[:1]
+
+
+class NotSubclassOfWindowEvaluator:
+    pass
+
+
+@pytest.fixture
+def ctx():
+    ctx = SessionContext()
+    batch = pa.RecordBatch.from_arrays(
+        [pa.array([4, 1, 2, 10, 20]), pa.array(["x", "x", "x", "y", "y"])],
+        names=["a", "b"],
+    )
+    ctx.register_record_batches("t", [[batch]])
+    return ctx
+
+
+def test_errors():
+    with pytest.raises(TypeError):
+        udwf(
+            NotSubclassOfWindowEvaluator,
+            pa.int64(),
+            pa.float64(),
+            volatility="immutable",
+        )
+
+
+def test_register_udwf(ctx):
+    trailing_avg = udwf(
+        TrailingAverage,
+        pa.int64(),
+        pa.float64(),
+        volatility="immutable",
+        name="trailing_avg",
+    )
+    ctx.register_udwf(trailing_avg)
+
+    result = ctx.sql(
+        "SELECT b, a, trailing_avg(a) OVER (PARTITION BY b ORDER BY a) AS avg "
+        "FROM t ORDER BY b, a"
+    ).to_pydict()
+
+    assert result["a"] == [1, 2, 4, 10, 20]
+    assert result["avg"] == [1.0, 1.5, 3.0, 10.0, 15.0]
+
+
+def test_window_builder_resolves_udwf(ctx):
+    trailing_avg = udwf(
+        TrailingAverage,
+        pa.int64(),
+        pa.float64(),
+        volatility="immutable",
+        name="trailing_avg",
+    )
+    ctx.register_udwf(trailing_avg)
+
+    df = ctx.table("t").select(
+        column("a"),
+        f.alias(
+            f.window(
+                "trailing_avg",
+                [column("a")],
+                partition_by=[column("b")],
+                order_by=[f.order_by(column("a"))],
+                ctx=ctx,
+            ),
+            "avg",
+        ),
+    )
+    result = df.sort(column("a").sort()).to_pydict()
+
+    assert result["a"] == [1, 2, 4, 10, 20]
+    assert result["avg"] == [1.0, 1.5, 3.0, 10.0, 15.0]
+
+
+def test_evaluate_all_must_return_one_value_per_row(ctx):
+    wrong_length = udwf(
+        WrongLength,
+        pa.int64(),
+        pa.float64(),
+        volatility="immutable",
+        name="wrong_length",
+    )
+    ctx.register_udwf(wrong_length)
+
+    df = ctx.sql("SELECT wrong_length(a) OVER (PARTITION BY b ORDER BY a) FROM t")
+    with pytest.raises(Exception, match="evaluate_all returned 1 values"):
+        df.collect()
@@ -0,0 +1,164 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Plans a single join as a broadcast join: a hash join collecting its left side into
+//! every partition (`mode=CollectLeft`), without changing how other joins are planned
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::error::Result;
+use datafusion::execution::context::{QueryPlanner, SessionState};
+use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode, SortMergeJoinExec};
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_planner::{DefaultPhysicalPlanner, ExtensionPlanner, PhysicalPlanner};
+use datafusion_common::DFSchemaRef;
+use datafusion_expr::{
+    Expr, Extension, LogicalPlan, UserDefinedLogicalNode, UserDefinedLogicalNodeCore,
+};
+
+/// Marks the join below it as a broadcast join. The node passes its input through
+/// unchanged, so the join is optimized like any other and only its physical
+/// operator is replaced.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct BroadcastJoin {
+    input: LogicalPlan,
+}
+
+impl UserDefinedLogicalNodeCore for BroadcastJoin {
+    fn name(&self) -> &str {
+        "BroadcastJoin"
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        self.input.schema()
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        vec![]
+    }
+
+    fn prevent_predicate_push_down_columns(&self) -> HashSet<String> {
+        HashSet::new()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BroadcastJoin")
+    }
+
+    fn with_exprs_and_inputs(
+        &self,
+        _exprs: Vec<Expr>,
+        mut inputs: Vec<LogicalPlan>,
+    ) -> Result<Self> {
+        Ok(Self {
+            input: inputs.swap_remove(0),
+        })
+    }
+
+    fn necessary_children_exprs(&self, output_columns: &[usize]) -> Option<Vec<Vec<usize>>> {
+        Some(vec![output_columns.to_vec()])
+    }
+}
+
+/// Wraps the join `plan` so that it is planned as a broadcast join
+pub(crate) fn broadcast_join(plan: LogicalPlan) -> LogicalPlan {
+    LogicalPlan::Extension(Extension {
+        node: Arc::new(BroadcastJoin { input: plan }),
+    })
+}
+
+/// Adds the planning of broadcast joins to `state`. Plans without them are planned
+/// exactly as by the default planner.
+pub(crate) fn with_broadcast_join_planner(state: SessionState) -> SessionState {
+    state.with_query_planner(Arc::new(BroadcastJoinQueryPlanner {}))
+}
+
+struct BroadcastJoinQueryPlanner {}
+
+#[async_trait]
+impl QueryPlanner for BroadcastJoinQueryPlanner {
+    async fn create_physical_plan(
+        &self,
+        logical_plan: &LogicalPlan,
+        session_state: &SessionState,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        DefaultPhysicalPlanner::with_extension_planners(vec![Arc::new(BroadcastJoinPlanner {})])
+            .create_physical_plan(logical_plan, session_state)
+            .await
+    }
+}
+
+struct BroadcastJoinPlanner {}
+
+#[async_trait]
+impl ExtensionPlanner for BroadcastJoinPlanner {
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        _logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _session_state: &SessionState,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        if node.as_any().downcast_ref::<BroadcastJoin>().is_none() {
+            return Ok(None);
+        }
+        collect_left(physical_inputs[0].clone()).map(Some)
+    }
+}
+
+/// Replans the join at the top of `plan`, below any projections, as a hash join in
+/// `CollectLeft` mode. Other plans are returned unchanged.
+fn collect_left(plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    let any = plan.as_any();
+    if let Some(join) = any.downcast_ref::<HashJoinExec>() {
+        Ok(Arc::new(HashJoinExec::try_new(
+            join.left().clone(),
+            join.right().clone(),
+            join.on().to_vec(),
+            join.filter().cloned(),
+            join.join_type(),
+            None,
+            PartitionMode::CollectLeft,
+            join.null_equals_null(),
+        )?))
+    } else if let Some(join) = any.downcast_ref::<SortMergeJoinExec>() {
+        Ok(Arc::new(HashJoinExec::try_new(
+            join.left.clone(),
+            join.right.clone(),
+            join.on.clone(),
+            join.filter.clone(),
+            &join.join_type,
+            None,
+            PartitionMode::CollectLeft,
+            join.null_equals_null,
+        )?))
+    } else if let Some(projection) = any.downcast_ref::<ProjectionExec>() {
+        let input = collect_left(projection.input().clone())?;
+        plan.with_new_children(vec![input])
+    } else {
+        Ok(plan)
+    }
+}
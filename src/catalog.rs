@@ -72,6 +72,13 @@ impl PyCatalog {
         self.catalog.schema_names()
     }
 
+    /// Returns the sorted names of the schemas in this catalog
+    fn schema_names(&self) -> Vec<String> {
+        let mut names = self.names();
+        names.sort();
+        names
+    }
+
     #[pyo3(signature = (name="public"))]
     fn database(&self, name: &str) -> PyResult<PyDatabase> {
         match self.catalog.schema(name) {
@@ -82,6 +89,12 @@ impl PyCatalog {
         }
     }
 
+    /// Alias for [`PyCatalog::database`] using DataFusion's naming
+    #[pyo3(signature = (name="public"))]
+    fn schema(&self, name: &str) -> PyResult<PyDatabase> {
+        self.database(name)
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         Ok(format!(
             "Catalog(schema_names=[{}])",
@@ -96,6 +109,17 @@ impl PyDatabase {
         self.database.table_names().into_iter().collect()
     }
 
+    /// Returns the sorted names of the tables in this schema
+    fn table_names(&self) -> Vec<String> {
+        let mut names = self.database.table_names();
+        names.sort();
+        names
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        self.database.table_exist(name)
+    }
+
     fn table(&self, name: &str, py: Python) -> PyResult<PyTable> {
         if let Some(table) = wait_for_future(py, self.database.table(name))? {
             Ok(PyTable::new(table))
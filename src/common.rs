@@ -20,6 +20,7 @@ use pyo3::prelude::*;
 pub mod data_type;
 pub mod df_schema;
 pub mod function;
+pub mod scalar_value;
 pub mod schema;
 
 /// Initializes the `common` module to match the pattern of `datafusion-common` https://docs.rs/datafusion-common/18.0.0/datafusion_common/index.html
@@ -34,5 +35,6 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<schema::SqlView>()?;
     m.add_class::<schema::SqlStatistics>()?;
     m.add_class::<function::SqlFunction>()?;
+    m.add_class::<scalar_value::PyScalarValue>()?;
     Ok(())
 }
@@ -0,0 +1,116 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use datafusion::arrow::array::{make_array, ArrayData};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::pyarrow::{FromPyArrow, PyArrowType, ToPyArrow};
+use datafusion_common::ScalarValue;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::errors::DataFusionError;
+
+/// A literal value with an exact Arrow type. It has the `type` and `as_py` of a
+/// pyarrow scalar, so it is accepted wherever a literal is.
+#[pyclass(name = "ScalarValue", module = "datafusion.common", subclass)]
+#[derive(Debug, Clone)]
+pub struct PyScalarValue {
+    pub value: ScalarValue,
+}
+
+impl From<ScalarValue> for PyScalarValue {
+    fn from(value: ScalarValue) -> PyScalarValue {
+        PyScalarValue { value }
+    }
+}
+
+#[pymethods]
+impl PyScalarValue {
+    /// A NULL of `data_type`
+    #[staticmethod]
+    fn null(data_type: PyArrowType<DataType>) -> PyResult<Self> {
+        Ok(ScalarValue::try_from(&data_type.0)
+            .map_err(DataFusionError::from)?
+            .into())
+    }
+
+    #[staticmethod]
+    fn boolean(value: Option<bool>) -> Self {
+        ScalarValue::Boolean(value).into()
+    }
+
+    #[staticmethod]
+    fn int32(value: Option<i32>) -> Self {
+        ScalarValue::Int32(value).into()
+    }
+
+    #[staticmethod]
+    fn int64(value: Option<i64>) -> Self {
+        ScalarValue::Int64(value).into()
+    }
+
+    #[staticmethod]
+    fn float32(value: Option<f32>) -> Self {
+        ScalarValue::Float32(value).into()
+    }
+
+    #[staticmethod]
+    fn float64(value: Option<f64>) -> Self {
+        ScalarValue::Float64(value).into()
+    }
+
+    #[staticmethod]
+    fn utf8(value: Option<String>) -> Self {
+        ScalarValue::Utf8(value).into()
+    }
+
+    /// The value of a pyarrow scalar, or of a pyarrow array with exactly one element
+    #[staticmethod]
+    fn from_arrow(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(data) = ArrayData::from_pyarrow_bound(value) {
+            if data.len() != 1 {
+                return Err(PyValueError::new_err(format!(
+                    "Expected an array with one element, got {}",
+                    data.len()
+                )));
+            }
+            let value =
+                ScalarValue::try_from_array(&make_array(data), 0).map_err(DataFusionError::from)?;
+            return Ok(value.into());
+        }
+        Ok(ScalarValue::from_pyarrow_bound(value)?.into())
+    }
+
+    #[getter]
+    fn r#type(&self) -> PyArrowType<DataType> {
+        PyArrowType(self.value.data_type())
+    }
+
+    fn is_null(&self) -> bool {
+        self.value.is_null()
+    }
+
+    /// The value as a Python object, as `pyarrow.Scalar.as_py` returns it
+    fn as_py(&self, py: Python) -> PyResult<PyObject> {
+        let array = self.value.to_array().map_err(DataFusionError::from)?;
+        let array = array.to_data().to_pyarrow(py)?;
+        Ok(array.bind(py).get_item(0)?.call_method0("as_py")?.unbind())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ScalarValue({:?})", self.value)
+    }
+}
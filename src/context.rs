@@ -21,45 +21,58 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use object_store::ObjectStore;
+use parking_lot::RwLock;
 use url::Url;
 use uuid::Uuid;
 
 use pyo3::exceptions::{PyKeyError, PyValueError};
 use pyo3::prelude::*;
 
+use crate::broadcast_join::with_broadcast_join_planner;
 use crate::catalog::{PyCatalog, PyTable};
 use crate::dataframe::PyDataFrame;
 use crate::dataset::Dataset;
 use crate::errors::{py_datafusion_err, DataFusionError};
 use crate::expr::PyExpr;
+use crate::file_format::{parse_file_format, DataFilesOnly};
+use crate::functions::session_time::session_time_functions;
+use crate::memory_pool::PeakMemoryPool;
+use crate::optimizer_rule::{PyAnalyzerRule, PyOptimizerRule};
 use crate::physical_plan::PyExecutionPlan;
 use crate::record_batch::PyRecordBatchStream;
 use crate::sql::logical::PyLogicalPlan;
 use crate::store::StorageContexts;
+#[cfg(feature = "substrait")]
+use crate::substrait::{PySubstraitConsumer, PySubstraitProducer, PySubstraitSerializer};
+use crate::table_provider::PyTableProvider;
 use crate::udaf::PyAggregateUDF;
 use crate::udf::PyScalarUDF;
-use crate::utils::{get_tokio_runtime, wait_for_future};
+use crate::udtf::PyTableFunction;
+use crate::udwf::PyWindowUDF;
+use crate::utils::{get_tokio_runtime, wait_for_future, LiteralValue};
 use datafusion::arrow::datatypes::{DataType, Schema, SchemaRef};
 use datafusion::arrow::pyarrow::PyArrowType;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
-use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::file_format::options::ReadOptions;
 use datafusion::datasource::listing::{
     ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
 };
 use datafusion::datasource::MemTable;
 use datafusion::datasource::TableProvider;
+use datafusion::datasource::ViewTable;
 use datafusion::execution::context::{
     SQLOptions, SessionConfig, SessionContext, SessionState, TaskContext,
 };
 use datafusion::execution::disk_manager::DiskManagerConfig;
 use datafusion::execution::memory_pool::{FairSpillPool, GreedyMemoryPool, UnboundedMemoryPool};
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
-use datafusion::physical_plan::SendableRecordBatchStream;
+use datafusion::physical_plan::{collect, SendableRecordBatchStream};
 use datafusion::prelude::{
     AvroReadOptions, CsvReadOptions, DataFrame, NdJsonReadOptions, ParquetReadOptions,
 };
-use datafusion_common::ScalarValue;
+use datafusion_common::{ParamValues, ScalarValue};
+use datafusion_expr::LogicalPlan;
 use pyo3::types::PyTuple;
 use tokio::task::JoinHandle;
 
@@ -147,6 +160,10 @@ impl PySessionConfig {
         Self::from(self.config.clone().with_repartition_file_min_size(size))
     }
 
+    fn with_coalesce_batches(&self, enabled: bool) -> Self {
+        Self::from(self.config.clone().with_coalesce_batches(enabled))
+    }
+
     fn with_parquet_pruning(&self, enabled: bool) -> Self {
         Self::from(self.config.clone().with_parquet_pruning(enabled))
     }
@@ -209,6 +226,15 @@ impl PyRuntimeConfig {
         Self { config }
     }
 
+    /// Limit the memory available to queries to `max_memory * memory_fraction` bytes.
+    /// Operators that can spill will do so once the limit is reached, other queries
+    /// exceeding it fail with a resources exhausted error.
+    fn with_memory_limit(&self, max_memory: usize, memory_fraction: f64) -> Self {
+        let config = self.config.clone();
+        let config = config.with_memory_limit(max_memory, memory_fraction);
+        Self { config }
+    }
+
     fn with_temp_file_path(&self, path: &str) -> Self {
         let config = self.config.clone();
         let config = config.with_temp_file_path(path);
@@ -253,6 +279,13 @@ impl PySQLOptions {
     }
 }
 
+/// Values for the placeholders of a SQL statement, by position or by name
+#[derive(FromPyObject)]
+pub enum SqlParams {
+    Named(HashMap<String, LiteralValue>),
+    Positional(Vec<LiteralValue>),
+}
+
 /// `PySessionContext` is able to plan and execute DataFusion plans.
 /// It has a powerful optimizer, a physical planner for local execution, and a
 /// multi-threaded execution engine to perform the execution.
@@ -260,6 +293,9 @@ impl PySQLOptions {
 #[derive(Clone)]
 pub struct PySessionContext {
     pub ctx: SessionContext,
+    /// Tracks the peak memory reservation when the runtime was created by this
+    /// context
+    memory_pool: Option<Arc<PeakMemoryPool>>,
 }
 
 #[pymethods]
@@ -275,18 +311,69 @@ impl PySessionContext {
         } else {
             SessionConfig::default().with_information_schema(true)
         };
-        let runtime_config = if let Some(c) = runtime {
+        let mut runtime_config = if let Some(c) = runtime {
             c.config
         } else {
             RuntimeConfig::default()
         };
+        let memory_pool = Arc::new(PeakMemoryPool::new(
+            runtime_config
+                .memory_pool
+                .take()
+                .unwrap_or_else(|| Arc::new(UnboundedMemoryPool::default())),
+        ));
+        runtime_config.memory_pool = Some(memory_pool.clone());
         let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
-        let session_state = SessionState::new_with_config_rt(config, runtime);
+        let session_state =
+            with_broadcast_join_planner(SessionState::new_with_config_rt(config, runtime));
+        let ctx = SessionContext::new_with_state(session_state);
+        // `now`, `current_date` and `current_time` follow the session time zone
+        for function in session_time_functions(ctx.state_weak_ref()) {
+            ctx.register_udf(function);
+        }
         Ok(PySessionContext {
-            ctx: SessionContext::new_with_state(session_state),
+            ctx,
+            memory_pool: Some(memory_pool),
         })
     }
 
+    /// Set the configuration option `key` of this session, such as
+    /// "datafusion.execution.time_zone", to `value`
+    pub fn set_config(&self, key: &str, value: &str) -> PyResult<()> {
+        self.state_lock()
+            .write()
+            .config_mut()
+            .options_mut()
+            .set(key, value)
+            .map_err(DataFusionError::from)?;
+        Ok(())
+    }
+
+    /// Returns the number of bytes currently reserved in the memory pool
+    pub fn memory_pool_reserved(&self) -> usize {
+        self.ctx.runtime_env().memory_pool.reserved()
+    }
+
+    /// Returns the largest number of bytes reserved in the memory pool at once since
+    /// the context was created or `reset_memory_pool_peak` was last called
+    pub fn memory_pool_peak(&self) -> PyResult<usize> {
+        match &self.memory_pool {
+            Some(pool) => Ok(pool.peak()),
+            None => Err(DataFusionError::Common(
+                "Memory peak is not tracked for this context".to_string(),
+            )
+            .into()),
+        }
+    }
+
+    /// Restarts peak tracking from the bytes currently reserved, so the next
+    /// `memory_pool_peak` reports the peak of the queries run in between
+    pub fn reset_memory_pool_peak(&self) {
+        if let Some(pool) = &self.memory_pool {
+            pool.reset_peak();
+        }
+    }
+
     /// Register an object store with the given name
     pub fn register_object_store(
         &mut self,
@@ -321,11 +408,15 @@ impl PySessionContext {
         Ok(())
     }
 
+    /// Register the files of `format` under `path` ending in `file_extension` as a
+    /// table. Hidden files, whose name starts with `.` or `_`, and empty files are
+    /// skipped, so marker files such as `_SUCCESS` do not break the table.
     #[allow(clippy::too_many_arguments)]
     #[pyo3(signature = (name, path, table_partition_cols=vec![],
     file_extension=".parquet",
     schema=None,
-    file_sort_order=None))]
+    file_sort_order=None,
+    format="parquet"))]
     pub fn register_listing_table(
         &mut self,
         name: &str,
@@ -334,9 +425,11 @@ impl PySessionContext {
         file_extension: &str,
         schema: Option<PyArrowType<Schema>>,
         file_sort_order: Option<Vec<Vec<PyExpr>>>,
+        format: &str,
         py: Python,
     ) -> PyResult<()> {
-        let options = ListingOptions::new(Arc::new(ParquetFormat::new()))
+        let format = DataFilesOnly::new(parse_file_format(format)?);
+        let options = ListingOptions::new(Arc::new(format))
             .with_file_extension(file_extension)
             .with_table_partition_cols(convert_table_partition_cols(table_partition_cols)?)
             .with_file_sort_order(
@@ -369,26 +462,56 @@ impl PySessionContext {
     }
 
     /// Returns a PyDataFrame whose plan corresponds to the SQL statement.
-    pub fn sql(&mut self, query: &str, py: Python) -> PyResult<PyDataFrame> {
+    /// DDL and DML statements are executed immediately, queries are executed lazily.
+    ///
+    /// `params` binds values to the placeholders of the statement, a list for `$1`,
+    /// `$2`, ... or a dict for named placeholders such as `$id`. Each value is cast to
+    /// the type inferred for its placeholder.
+    #[pyo3(signature = (query, params=None))]
+    pub fn sql(
+        &mut self,
+        query: &str,
+        params: Option<SqlParams>,
+        py: Python,
+    ) -> PyResult<PyDataFrame> {
         let result = self.ctx.sql(query);
-        let df = wait_for_future(py, result).map_err(DataFusionError::from)?;
-        Ok(PyDataFrame::new(df))
+        let mut df = wait_for_future(py, result).map_err(DataFusionError::from)?;
+        if let Some(params) = params {
+            df = bind_params(df, params)?;
+        }
+        Ok(PyDataFrame::new(self.execute_dml(df, py)?))
     }
 
+    /// Like `sql`, but the statement is first checked against `options`. The
+    /// `allow_ddl`, `allow_dml` and `allow_statements` flags override the matching
+    /// setting of `options`, so `allow_ddl=False` rejects `CREATE` and `DROP`.
+    #[pyo3(signature = (query, options=None, allow_ddl=None, allow_dml=None, allow_statements=None))]
     pub fn sql_with_options(
         &mut self,
         query: &str,
         options: Option<PySQLOptions>,
+        allow_ddl: Option<bool>,
+        allow_dml: Option<bool>,
+        allow_statements: Option<bool>,
         py: Python,
     ) -> PyResult<PyDataFrame> {
-        let options = if let Some(options) = options {
+        let mut options = if let Some(options) = options {
             options.options
         } else {
             SQLOptions::new()
         };
+        if let Some(allow) = allow_ddl {
+            options = options.with_allow_ddl(allow);
+        }
+        if let Some(allow) = allow_dml {
+            options = options.with_allow_dml(allow);
+        }
+        if let Some(allow) = allow_statements {
+            options = options.with_allow_statements(allow);
+        }
         let result = self.ctx.sql_with_options(query, options);
         let df = wait_for_future(py, result).map_err(DataFusionError::from)?;
-        Ok(PyDataFrame::new(df))
+        Ok(PyDataFrame::new(self.execute_dml(df, py)?))
     }
 
     pub fn create_dataframe(
@@ -401,7 +524,7 @@ impl PySessionContext {
         let schema = if let Some(schema) = schema {
             SchemaRef::from(schema.0)
         } else {
-            partitions.0[0][0].schema()
+            partitions_schema(&partitions.0)?
         };
 
         let table = MemTable::try_new(schema, partitions.0).map_err(DataFusionError::from)?;
@@ -433,6 +556,25 @@ impl PySessionContext {
         PyDataFrame::new(DataFrame::new(self.ctx.state(), plan.plan.as_ref().clone()))
     }
 
+    /// Serialize the logical plan of a DataFrame to Substrait protobuf bytes
+    #[cfg(feature = "substrait")]
+    pub fn substrait_producer(&self, df: PyDataFrame, py: Python) -> PyResult<PyObject> {
+        let plan = PySubstraitProducer::to_substrait_plan(df.logical_plan()?, self)?;
+        plan.encode(py)
+    }
+
+    /// Create a DataFrame from Substrait protobuf bytes produced by `substrait_producer`
+    #[cfg(feature = "substrait")]
+    pub fn substrait_consumer(
+        &mut self,
+        proto_bytes: Vec<u8>,
+        py: Python,
+    ) -> PyResult<PyDataFrame> {
+        let plan = PySubstraitSerializer::deserialize_bytes(proto_bytes, py)?;
+        let logical_plan = PySubstraitConsumer::from_substrait_plan(self, plan, py)?;
+        Ok(self.create_dataframe_from_logical_plan(logical_plan))
+    }
+
     /// Construct datafusion dataframe from Python list
     #[allow(clippy::wrong_self_convention)]
     pub fn from_pylist(
@@ -542,19 +684,46 @@ impl PySessionContext {
         Ok(())
     }
 
-    pub fn deregister_table(&mut self, name: &str) -> PyResult<()> {
+    /// Register the logical plan of `df` as a view named `name`. The plan is planned
+    /// again each time the view is queried, so the view follows the current data of
+    /// the tables it reads. An existing table of the same name is an error unless
+    /// `or_replace` is set.
+    #[pyo3(signature = (name, df, or_replace=false))]
+    pub fn register_view(&mut self, name: &str, df: PyDataFrame, or_replace: bool) -> PyResult<()> {
+        if self.ctx.table_exist(name)? {
+            if !or_replace {
+                return Err(PyValueError::new_err(format!(
+                    "Table '{name}' already exists, use or_replace=True to replace it"
+                )));
+            }
+            self.ctx
+                .deregister_table(name)
+                .map_err(DataFusionError::from)?;
+        }
+        let view = ViewTable::try_new(df.logical_plan()?.plan().as_ref().clone(), None)?;
         self.ctx
-            .deregister_table(name)
+            .register_table(name, Arc::new(view))
             .map_err(DataFusionError::from)?;
         Ok(())
     }
 
+    /// Remove the table `name`, returning whether it was registered
+    pub fn deregister_table(&mut self, name: &str) -> PyResult<bool> {
+        let table = self
+            .ctx
+            .deregister_table(name)
+            .map_err(DataFusionError::from)?;
+        Ok(table.is_some())
+    }
+
+    /// Register the record batches as an in-memory table `name`, each inner list
+    /// becoming one partition. All batches must have the same schema.
     pub fn register_record_batches(
         &mut self,
         name: &str,
         partitions: PyArrowType<Vec<Vec<RecordBatch>>>,
     ) -> PyResult<()> {
-        let schema = partitions.0[0][0].schema();
+        let schema = partitions_schema(&partitions.0)?;
         let table = MemTable::try_new(schema, partitions.0)?;
         self.ctx
             .register_table(name, Arc::new(table))
@@ -568,7 +737,8 @@ impl PySessionContext {
                         file_extension=".parquet",
                         skip_metadata=true,
                         schema=None,
-                        file_sort_order=None))]
+                        file_sort_order=None,
+                        enable_page_index=true))]
     pub fn register_parquet(
         &mut self,
         name: &str,
@@ -579,6 +749,7 @@ impl PySessionContext {
         skip_metadata: bool,
         schema: Option<PyArrowType<Schema>>,
         file_sort_order: Option<Vec<Vec<PyExpr>>>,
+        enable_page_index: bool,
         py: Python,
     ) -> PyResult<()> {
         let mut options = ParquetReadOptions::default()
@@ -593,7 +764,14 @@ impl PySessionContext {
             .map(|e| e.into_iter().map(|f| f.into()).collect())
             .collect();
 
-        let result = self.ctx.register_parquet(name, path, options);
+        let listing_options = self.parquet_listing_options(&options, enable_page_index);
+        let result = self.ctx.register_listing_table(
+            name,
+            path,
+            listing_options,
+            options.schema.map(|s| Arc::new(s.to_owned())),
+            None,
+        );
         wait_for_future(py, result).map_err(DataFusionError::from)?;
         Ok(())
     }
@@ -606,7 +784,8 @@ impl PySessionContext {
                         delimiter=",",
                         schema_infer_max_records=1000,
                         file_extension=".csv",
-                        file_compression_type=None))]
+                        file_compression_type=None,
+                        column_types=None))]
     pub fn register_csv(
         &mut self,
         name: &str,
@@ -617,6 +796,7 @@ impl PySessionContext {
         schema_infer_max_records: usize,
         file_extension: &str,
         file_compression_type: Option<String>,
+        column_types: Option<HashMap<String, PyArrowType<DataType>>>,
         py: Python,
     ) -> PyResult<()> {
         let path = path
@@ -635,7 +815,24 @@ impl PySessionContext {
             .schema_infer_max_records(schema_infer_max_records)
             .file_extension(file_extension)
             .file_compression_type(parse_file_compression_type(file_compression_type)?);
-        options.schema = schema.as_ref().map(|x| &x.0);
+        // `column_types` overrides the types of some columns of the inferred schema.
+        // Values that do not parse as the given type fail the read, as they would
+        // with a full `schema`.
+        let schema = match (schema, column_types) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "Only one of schema and column_types can be given",
+                ))
+            }
+            (schema, None) => schema.map(|s| s.0),
+            (None, Some(column_types)) => {
+                let listing_options = options
+                    .to_listing_options(&self.ctx.copied_config(), self.ctx.copied_table_options());
+                let inferred = self.infer_schema(path, &listing_options, py)?.0;
+                Some(with_column_types(inferred, column_types)?)
+            }
+        };
+        options.schema = schema.as_ref();
 
         let result = self.ctx.register_csv(name, path, options);
         wait_for_future(py, result).map_err(DataFusionError::from)?;
@@ -725,6 +922,21 @@ impl PySessionContext {
         Ok(())
     }
 
+    /// Registers a table implemented in Python. `provider` must implement `schema()`
+    /// returning a pyarrow Schema and `scan(projection, filters, limit)` returning record
+    /// batches (or a pyarrow Table) of the projected columns. It may implement
+    /// `supports_filters_pushdown(filters)` returning "exact", "inexact" or "unsupported"
+    /// for each filter; filters not applied exactly are re-applied by DataFusion.
+    pub fn register_table_provider(&self, name: &str, provider: &Bound<'_, PyAny>) -> PyResult<()> {
+        let table: Arc<dyn TableProvider> = Arc::new(PyTableProvider::new(provider)?);
+
+        self.ctx
+            .register_table(name, table)
+            .map_err(DataFusionError::from)?;
+
+        Ok(())
+    }
+
     pub fn register_udf(&mut self, udf: PyScalarUDF) -> PyResult<()> {
         self.ctx.register_udf(udf.function);
         Ok(())
@@ -735,6 +947,55 @@ impl PySessionContext {
         Ok(())
     }
 
+    pub fn register_udwf(&mut self, udwf: PyWindowUDF) -> PyResult<()> {
+        self.ctx.register_udwf(udwf.function);
+        Ok(())
+    }
+
+    /// Adds an optimizer rule written in Python. `rule` implements `try_optimize(plan)`
+    /// receiving the `LogicalPlan` being optimized and returning a rewritten plan with
+    /// the same schema, or None to leave it unchanged. It may implement `name()`.
+    pub fn add_optimizer_rule(&self, rule: &Bound<'_, PyAny>) -> PyResult<()> {
+        let rule = Arc::new(PyOptimizerRule::new(rule)?);
+        // the state is updated in place, as the session time functions hold a
+        // reference to it
+        let state = self.state_lock();
+        let mut state = state.write();
+        let updated = state.clone().add_optimizer_rule(rule);
+        *state = updated;
+        Ok(())
+    }
+
+    /// Adds an analyzer rule written in Python. `rule` implements `analyze(plan)`
+    /// receiving the `LogicalPlan` before optimization and returning a rewritten plan,
+    /// or None to leave it unchanged. It may implement `name()`.
+    pub fn add_analyzer_rule(&self, rule: &Bound<'_, PyAny>) -> PyResult<()> {
+        let rule = Arc::new(PyAnalyzerRule::new(rule)?);
+        // the state is updated in place, as the session time functions hold a
+        // reference to it
+        let state = self.state_lock();
+        let mut state = state.write();
+        let updated = state.clone().add_analyzer_rule(rule);
+        *state = updated;
+        Ok(())
+    }
+
+    /// Registers a table function callable as `name(...)` in the FROM clause of SQL
+    /// queries. `func` receives the literal arguments of the call and returns a
+    /// `pyarrow.Table` or a list of record batches.
+    pub fn register_udtf(&mut self, name: &str, func: PyObject) -> PyResult<()> {
+        self.ctx
+            .register_udtf(name, Arc::new(PyTableFunction::new(name, func)));
+        Ok(())
+    }
+
+    /// Returns the names of all registered catalogs
+    pub fn catalog_names(&self) -> Vec<String> {
+        let mut names = self.ctx.catalog_names();
+        names.sort();
+        names
+    }
+
     #[pyo3(signature = (name="datafusion"))]
     pub fn catalog(&self, name: &str) -> PyResult<PyCatalog> {
         match self.ctx.catalog(name) {
@@ -770,8 +1031,32 @@ impl PySessionContext {
         Ok(self.ctx.table_exist(name)?)
     }
 
-    pub fn empty_table(&self) -> PyResult<PyDataFrame> {
-        Ok(PyDataFrame::new(self.ctx.read_empty()?))
+    /// Whether the table `name` is registered, the same as `table_exist`
+    pub fn table_exists(&self, name: &str) -> PyResult<bool> {
+        self.table_exist(name)
+    }
+
+    /// Returns a DataFrame without rows. With a `schema` the DataFrame has its columns,
+    /// which may be none, and is registered as the table `name` when one is given.
+    #[pyo3(signature = (schema=None, name=None))]
+    pub fn empty_table(
+        &self,
+        schema: Option<PyArrowType<Schema>>,
+        name: Option<&str>,
+    ) -> PyResult<PyDataFrame> {
+        let Some(schema) = schema else {
+            if name.is_some() {
+                return Err(PyValueError::new_err(
+                    "A schema is required to register an empty table",
+                ));
+            }
+            return Ok(PyDataFrame::new(self.ctx.read_empty()?));
+        };
+        let table = Arc::new(MemTable::try_new(Arc::new(schema.0), vec![vec![]])?);
+        if let Some(name) = name {
+            self.ctx.register_table(name, table.clone())?;
+        }
+        Ok(PyDataFrame::new(self.ctx.read_table(table)?))
     }
 
     pub fn session_id(&self) -> String {
@@ -870,7 +1155,8 @@ impl PySessionContext {
         file_extension=".parquet",
         skip_metadata=true,
         schema=None,
-        file_sort_order=None))]
+        file_sort_order=None,
+        enable_page_index=true))]
     pub fn read_parquet(
         &self,
         path: &str,
@@ -880,6 +1166,7 @@ impl PySessionContext {
         skip_metadata: bool,
         schema: Option<PyArrowType<Schema>>,
         file_sort_order: Option<Vec<Vec<PyExpr>>>,
+        enable_page_index: bool,
         py: Python,
     ) -> PyResult<PyDataFrame> {
         let mut options = ParquetReadOptions::default()
@@ -894,9 +1181,25 @@ impl PySessionContext {
             .map(|e| e.into_iter().map(|f| f.into()).collect())
             .collect();
 
-        let result = self.ctx.read_parquet(path, options);
-        let df = PyDataFrame::new(wait_for_future(py, result).map_err(DataFusionError::from)?);
-        Ok(df)
+        let listing_options = self.parquet_listing_options(&options, enable_page_index);
+        let table_path = ListingTableUrl::parse(path)?;
+        let resolved_schema: SchemaRef = match options.schema {
+            Some(s) => Arc::new(s.to_owned()),
+            None => {
+                let state = self.ctx.state();
+                let schema = listing_options.infer_schema(&state, &table_path);
+                wait_for_future(py, schema).map_err(DataFusionError::from)?
+            }
+        };
+        let config = ListingTableConfig::new(table_path)
+            .with_listing_options(listing_options)
+            .with_schema(resolved_schema);
+        let table = ListingTable::try_new(config)?;
+        let df = self
+            .ctx
+            .read_table(Arc::new(table))
+            .map_err(DataFusionError::from)?;
+        Ok(PyDataFrame::new(df))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -923,6 +1226,76 @@ impl PySessionContext {
         Ok(PyDataFrame::new(df))
     }
 
+    /// Returns the schema of the parquet files at `path`, read from their footers
+    /// without reading any row groups.
+    #[pyo3(signature = (path, file_extension=".parquet", skip_metadata=true))]
+    pub fn read_parquet_schema(
+        &self,
+        path: &str,
+        file_extension: &str,
+        skip_metadata: bool,
+        py: Python,
+    ) -> PyResult<PyArrowType<Schema>> {
+        let mut options = ParquetReadOptions::default().skip_metadata(skip_metadata);
+        options.file_extension = file_extension;
+        let listing_options = self.parquet_listing_options(&options, true);
+        self.infer_schema(path, &listing_options, py)
+    }
+
+    /// Returns the schema of the Avro files at `path`, read from their headers.
+    #[pyo3(signature = (path, file_extension=".avro"))]
+    pub fn read_avro_schema(
+        &self,
+        path: &str,
+        file_extension: &str,
+        py: Python,
+    ) -> PyResult<PyArrowType<Schema>> {
+        let options = AvroReadOptions {
+            file_extension,
+            ..Default::default()
+        };
+        let listing_options =
+            options.to_listing_options(&self.ctx.copied_config(), self.ctx.copied_table_options());
+        self.infer_schema(path, &listing_options, py)
+    }
+
+    /// Returns the schema of the CSV files at `path`, inferred from their first
+    /// `schema_infer_max_records` rows.
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        path,
+        has_header=true,
+        delimiter=",",
+        schema_infer_max_records=1000,
+        file_extension=".csv",
+        file_compression_type=None))]
+    pub fn read_csv_schema(
+        &self,
+        path: &str,
+        has_header: bool,
+        delimiter: &str,
+        schema_infer_max_records: usize,
+        file_extension: &str,
+        file_compression_type: Option<String>,
+        py: Python,
+    ) -> PyResult<PyArrowType<Schema>> {
+        let delimiter = delimiter.as_bytes();
+        if delimiter.len() != 1 {
+            return Err(PyValueError::new_err(
+                "Delimiter must be a single character",
+            ));
+        };
+        let options = CsvReadOptions::new()
+            .has_header(has_header)
+            .delimiter(delimiter[0])
+            .schema_infer_max_records(schema_infer_max_records)
+            .file_extension(file_extension)
+            .file_compression_type(parse_file_compression_type(file_compression_type)?);
+        let listing_options =
+            options.to_listing_options(&self.ctx.copied_config(), self.ctx.copied_table_options());
+        self.infer_schema(path, &listing_options, py)
+    }
+
     pub fn read_table(&self, table: &PyTable) -> PyResult<PyDataFrame> {
         let df = self
             .ctx
@@ -948,13 +1321,24 @@ impl PySessionContext {
         ))
     }
 
-    /// Execute a partition of an execution plan and return a stream of record batches
+    /// Execute a partition of an execution plan and return a stream of record batches.
+    /// Partitions are numbered from 0 to `plan.partition_count - 1`.
     pub fn execute(
         &self,
         plan: PyExecutionPlan,
         part: usize,
         py: Python,
     ) -> PyResult<PyRecordBatchStream> {
+        let partition_count = plan
+            .plan
+            .properties()
+            .output_partitioning()
+            .partition_count();
+        if part >= partition_count {
+            return Err(PyValueError::new_err(format!(
+                "Partition {part} is out of range, the plan has {partition_count} partitions"
+            )));
+        }
         let ctx: TaskContext = TaskContext::from(&self.ctx.state());
         // create a Tokio runtime to run the async code
         let rt = &get_tokio_runtime(py).0;
@@ -967,9 +1351,154 @@ impl PySessionContext {
 }
 
 impl PySessionContext {
+    /// The shared state of the session, for changes that must be seen by everything
+    /// holding a reference to it rather than by a copy
+    fn state_lock(&self) -> Arc<RwLock<SessionState>> {
+        self.ctx
+            .state_weak_ref()
+            .upgrade()
+            .expect("the session context owns its state")
+    }
+
+    /// Listing options for reading parquet files with `options`. With
+    /// `enable_page_index` the page index of the files, when present, is read to skip
+    /// the pages a filter cannot match.
+    fn parquet_listing_options(
+        &self,
+        options: &ParquetReadOptions<'_>,
+        enable_page_index: bool,
+    ) -> ListingOptions {
+        let mut table_options = self.ctx.copied_table_options();
+        table_options.parquet.global.enable_page_index = enable_page_index;
+        options.to_listing_options(&self.ctx.copied_config(), table_options)
+    }
+
+    /// Infer the schema of the files at `path` from their metadata, as far as the
+    /// format allows without reading the data
+    fn infer_schema(
+        &self,
+        path: &str,
+        listing_options: &ListingOptions,
+        py: Python,
+    ) -> PyResult<PyArrowType<Schema>> {
+        let table_path = ListingTableUrl::parse(path)?;
+        let state = self.ctx.state();
+        let schema = listing_options.infer_schema(&state, &table_path);
+        let schema = wait_for_future(py, schema).map_err(DataFusionError::from)?;
+        Ok(PyArrowType(schema.as_ref().clone()))
+    }
+
     async fn _table(&self, name: &str) -> datafusion_common::Result<DataFrame> {
         self.ctx.table(name).await
     }
+
+    /// DataFusion plans DML statements lazily. Execute them right away so the side
+    /// effect happens, and return a DataFrame holding the resulting row count.
+    /// Statements that cannot be planned, such as an insert into a read-only table,
+    /// stay lazy and raise when the DataFrame is collected.
+    fn execute_dml(&self, df: DataFrame, py: Python) -> PyResult<DataFrame> {
+        if !matches!(
+            df.logical_plan(),
+            LogicalPlan::Dml(_) | LogicalPlan::Copy(_)
+        ) {
+            return Ok(df);
+        }
+        let schema = Arc::new(Schema::from(df.schema()));
+        let task_ctx = Arc::new(df.task_ctx());
+        let Ok(plan) = wait_for_future(py, df.clone().create_physical_plan()) else {
+            return Ok(df);
+        };
+        let batches =
+            wait_for_future(py, collect(plan, task_ctx)).map_err(DataFusionError::from)?;
+        let table = MemTable::try_new(schema, vec![batches]).map_err(DataFusionError::from)?;
+        let df = self
+            .ctx
+            .read_table(Arc::new(table))
+            .map_err(DataFusionError::from)?;
+        Ok(df)
+    }
+}
+
+/// Replace the types of the fields of `schema` named in `column_types`
+fn with_column_types(
+    schema: Schema,
+    mut column_types: HashMap<String, PyArrowType<DataType>>,
+) -> PyResult<Schema> {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| match column_types.remove(field.name()) {
+            Some(data_type) => field.as_ref().clone().with_data_type(data_type.0),
+            None => field.as_ref().clone(),
+        })
+        .collect::<Vec<_>>();
+    if let Some(name) = column_types.keys().next() {
+        let available = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        return Err(PyValueError::new_err(format!(
+            "Column '{name}' in column_types is not in the file, available columns: {}",
+            available.join(", ")
+        )));
+    }
+    Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+/// Bind `params` to the placeholders of `df`, casting each value to the type inferred
+/// for its placeholder
+fn bind_params(df: DataFrame, params: SqlParams) -> PyResult<DataFrame> {
+    let types = df
+        .logical_plan()
+        .get_parameter_types()
+        .map_err(DataFusionError::from)?;
+    let bind = |id: String, value: ScalarValue| match types.get(&id) {
+        Some(Some(data_type)) => value.cast_to(data_type),
+        _ => Ok(value),
+    };
+    let (given, values) = match params {
+        SqlParams::Positional(values) => {
+            let given = (1..=values.len())
+                .map(|i| format!("${i}"))
+                .collect::<HashSet<_>>();
+            let values = values
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| bind(format!("${}", i + 1), value.0))
+                .collect::<datafusion_common::Result<Vec<_>>>()
+                .map_err(DataFusionError::from)?;
+            (given, ParamValues::List(values))
+        }
+        SqlParams::Named(values) => {
+            let given = values.keys().map(|name| format!("${name}")).collect();
+            let values = values
+                .into_iter()
+                .map(|(name, value)| Ok((name.clone(), bind(format!("${name}"), value.0)?)))
+                .collect::<datafusion_common::Result<HashMap<_, _>>>()
+                .map_err(DataFusionError::from)?;
+            (given, ParamValues::Map(values))
+        }
+    };
+
+    let mut missing = types
+        .keys()
+        .filter(|id| !given.contains(*id))
+        .collect::<Vec<_>>();
+    missing.sort();
+    if !missing.is_empty() {
+        return Err(PyValueError::new_err(format!(
+            "No value given for parameter {}",
+            missing
+                .iter()
+                .map(|id| id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    Ok(df
+        .with_param_values(values)
+        .map_err(DataFusionError::from)?)
 }
 
 pub fn convert_table_partition_cols(
@@ -1004,6 +1533,44 @@ impl From<PySessionContext> for SessionContext {
 
 impl From<SessionContext> for PySessionContext {
     fn from(ctx: SessionContext) -> PySessionContext {
-        PySessionContext { ctx }
+        PySessionContext {
+            ctx,
+            memory_pool: None,
+        }
     }
 }
+
+/// The schema shared by all record batches of `partitions`
+fn partitions_schema(partitions: &[Vec<RecordBatch>]) -> PyResult<SchemaRef> {
+    let mut batches = partitions.iter().enumerate().flat_map(|(p, batches)| {
+        batches
+            .iter()
+            .enumerate()
+            .map(move |(b, batch)| (p, b, batch))
+    });
+    let Some((_, _, first)) = batches.next() else {
+        return Err(PyValueError::new_err(
+            "At least one record batch is required to infer the schema",
+        ));
+    };
+    let schema = first.schema();
+    for (p, b, batch) in batches {
+        if !schema.contains(&batch.schema()) {
+            return Err(PyValueError::new_err(format!(
+                "Record batch {b} of partition {p} has schema {}, expected {}",
+                describe_schema(&batch.schema()),
+                describe_schema(&schema)
+            )));
+        }
+    }
+    Ok(schema)
+}
+
+fn describe_schema(schema: &Schema) -> String {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|f| format!("{}: {}", f.name(), f.data_type()))
+        .collect::<Vec<_>>();
+    format!("[{}]", fields.join(", "))
+}
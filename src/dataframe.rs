@@ -15,30 +15,67 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
-use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::array::{Array, ArrayRef, AsArray, StringBuilder};
+use datafusion::arrow::datatypes::{DataType, Field, Fields, IntervalUnit, Schema};
+use datafusion::arrow::error::ArrowError;
 use datafusion::arrow::pyarrow::{PyArrowType, ToPyArrow};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::util::display::{ArrayFormatter, FormatOptions};
 use datafusion::arrow::util::pretty;
 use datafusion::config::TableParquetOptions;
 use datafusion::dataframe::{DataFrame, DataFrameWriteOptions};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::datasource::physical_plan::ParquetExec;
+use datafusion::execution::context::SessionState;
 use datafusion::execution::SendableRecordBatchStream;
+use datafusion::functions_aggregate::expr_fn::sum;
 use datafusion::parquet::basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel};
+use datafusion::physical_plan::metrics::MetricsSet;
+use datafusion::physical_plan::ExecutionPlan;
 use datafusion::prelude::*;
-use datafusion_common::UnnestOptions;
+use datafusion_common::cast::{as_int64_array, as_uint64_array};
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{Column, DFSchema, ScalarValue, TableReference, UnnestOptions};
+use datafusion_expr::expr::{TryCast, WindowFunction};
+use datafusion_expr::utils::conjunction;
+use datafusion_expr::{
+    BuiltInWindowFunction, ExprSchemable, LogicalPlan, LogicalPlanBuilder, Projection, WindowFrame,
+    WindowFunctionDefinition,
+};
+use futures::TryStreamExt;
+use parking_lot::Mutex;
 use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
-use pyo3::types::PyTuple;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use regex::Regex;
 use tokio::task::JoinHandle;
 
+use crate::broadcast_join::{broadcast_join, with_broadcast_join_planner};
 use crate::errors::py_datafusion_err;
+use crate::functions::fill_null::common_value_type;
+use crate::functions::seeded_random::seeded_random;
 use crate::physical_plan::PyExecutionPlan;
 use crate::record_batch::PyRecordBatchStream;
 use crate::sql::logical::PyLogicalPlan;
-use crate::utils::{get_tokio_runtime, wait_for_future};
+use crate::utils::{
+    get_tokio_runtime, wait_for_future, wait_for_future_interruptible, LiteralValue,
+};
 use crate::{errors::DataFusionError, expr::PyExpr};
 
+/// Name of the helper column holding the rank of each row in `top_k`
+const TOP_K_ROW_NUMBER: &str = "__top_k_row_number";
+
+/// Name of the helper column numbering the copies of each row in `intersect_all` and
+/// `except_all`
+const COPY_NUMBER: &str = "__copy_number";
+
+/// Name of the helper column holding the number of rows of each key in `assert_unique`
+const KEY_COUNT: &str = "__key_count";
+
 /// A PyDataFrame is a representation of a logical plan and an API to compose statements.
 /// Use it to build a plan and `.collect()` to execute the plan and collect the result.
 /// The actual execution of a plan runs natively on Rust and Arrow on a multi-threaded environment.
@@ -46,12 +83,118 @@ use crate::{errors::DataFusionError, expr::PyExpr};
 #[derive(Clone)]
 pub struct PyDataFrame {
     df: Arc<DataFrame>,
+    last_execution: Arc<Mutex<Option<LastExecution>>>,
+}
+
+/// The physical plan run by the last `collect` of a DataFrame, kept for its metrics
+struct LastExecution {
+    plan: Arc<dyn ExecutionPlan>,
+    output_rows: usize,
 }
 
 impl PyDataFrame {
     /// creates a new PyDataFrame
     pub fn new(df: DataFrame) -> Self {
-        Self { df: Arc::new(df) }
+        Self {
+            df: Arc::new(df),
+            last_execution: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Set operations match columns by position, so both sides need the same number of
+    /// columns with the same types
+    fn check_same_schema(&self, other: &PyDataFrame, operation: &str) -> PyResult<()> {
+        let left = self.df.schema().fields();
+        let right = other.df.schema().fields();
+        let compatible = left.len() == right.len()
+            && left
+                .iter()
+                .zip(right.iter())
+                .all(|(l, r)| l.data_type().equals_datatype(r.data_type()));
+        if !compatible {
+            let describe = |fields: &Fields| {
+                fields
+                    .iter()
+                    .map(|f| format!("{}: {}", f.name(), f.data_type()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            return Err(PyValueError::new_err(format!(
+                "{operation} requires DataFrames with the same schema, got [{}] and [{}]",
+                describe(left),
+                describe(right)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Count the nulls of each of `columns` with a single aggregate over the data
+    fn exact_null_counts(&self, columns: &[Column], py: Python) -> PyResult<Vec<usize>> {
+        if columns.is_empty() {
+            return Ok(vec![]);
+        }
+        let aggs = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let is_null = cast(Expr::Column(column.clone()).is_null(), DataType::Int64);
+                sum(is_null).alias(format!("null_count_{i}"))
+            })
+            .collect();
+        let df = self.df.as_ref().clone().aggregate(vec![], aggs)?;
+        let batches = wait_for_future(py, df.collect())?;
+
+        let mut counts = vec![0; columns.len()];
+        if let Some(batch) = batches.first() {
+            for (count, array) in counts.iter_mut().zip(batch.columns()) {
+                let array = as_int64_array(array)?;
+                if array.is_valid(0) {
+                    *count = array.value(0) as usize;
+                }
+            }
+        }
+        Ok(counts)
+    }
+
+    /// The distinct values of `column`, in sorted order
+    fn distinct_values(&self, column: &str, py: Python) -> PyResult<Vec<ScalarValue>> {
+        let df = self
+            .df
+            .as_ref()
+            .clone()
+            .select(vec![col(column)])?
+            .distinct()?
+            .sort(vec![col(column).sort(true, false)])?;
+        let batches = wait_for_future(py, df.collect())?;
+        let mut values = vec![];
+        for batch in batches {
+            for row in 0..batch.num_rows() {
+                values.push(ScalarValue::try_from_array(batch.column(0), row)?);
+            }
+        }
+        Ok(values)
+    }
+
+    /// Project every column of the DataFrame, replacing the ones named in `new_columns`
+    /// in place and appending the others in order
+    fn project_new_columns(&self, mut new_columns: Vec<(String, Expr)>) -> PyResult<Self> {
+        let mut projection = vec![];
+        for (qualifier, field) in self.df.schema().iter() {
+            match new_columns
+                .iter()
+                .position(|(name, _)| name == field.name())
+            {
+                Some(idx) => {
+                    let (name, expr) = new_columns.remove(idx);
+                    projection.push(expr.alias(name));
+                }
+                None => projection.push(Expr::Column(Column::from((qualifier, field.as_ref())))),
+            }
+        }
+        projection.extend(new_columns.into_iter().map(|(name, expr)| expr.alias(name)));
+
+        let df = self.df.as_ref().clone().select(projection)?;
+        Ok(Self::new(df))
     }
 }
 
@@ -61,17 +204,17 @@ impl PyDataFrame {
     fn __getitem__(&self, key: Bound<'_, PyAny>) -> PyResult<Self> {
         if let Ok(key) = key.extract::<PyBackedStr>() {
             // df[col]
-            self.select_columns(vec![key])
+            self.select_columns(vec![key], false)
         } else if let Ok(tuple) = key.extract::<&PyTuple>() {
             // df[col1, col2, col3]
             let keys = tuple
                 .iter()
                 .map(|item| item.extract::<PyBackedStr>())
                 .collect::<PyResult<Vec<PyBackedStr>>>()?;
-            self.select_columns(keys)
+            self.select_columns(keys, false)
         } else if let Ok(keys) = key.extract::<Vec<PyBackedStr>>() {
             // df[[col1, col2, col3]]
-            self.select_columns(keys)
+            self.select_columns(keys, false)
         } else {
             let message = "DataFrame can only be indexed by string index or indices";
             Err(PyTypeError::new_err(message))
@@ -100,10 +243,87 @@ impl PyDataFrame {
         PyArrowType(self.df.schema().into())
     }
 
-    #[pyo3(signature = (*args))]
-    fn select_columns(&self, args: Vec<PyBackedStr>) -> PyResult<Self> {
-        let args = args.iter().map(|s| s.as_ref()).collect::<Vec<&str>>();
-        let df = self.df.as_ref().clone().select_columns(&args)?;
+    /// Returns the table qualifier of each field of `schema()`, in order, or None for
+    /// fields without one, such as computed columns
+    fn schema_qualifiers(&self) -> Vec<Option<String>> {
+        self.df
+            .schema()
+            .iter()
+            .map(|(qualifier, _)| qualifier.map(|q| q.to_string()))
+            .collect()
+    }
+
+    /// Select columns by name. Names containing `*` or `?` are glob patterns, and with
+    /// `regex=True` every name is a regular expression searched in the column names.
+    /// The columns matching a pattern are selected in schema order, each column once.
+    #[pyo3(signature = (*args, regex=false))]
+    fn select_columns(&self, args: Vec<PyBackedStr>, regex: bool) -> PyResult<Self> {
+        let schema = self.df.schema();
+        let mut selected: Vec<(Option<&TableReference>, &Field)> = vec![];
+        for name in args.iter().map(|s| s.as_ref()) {
+            let pattern = if regex {
+                Some(name_regex(name)?)
+            } else if name.contains(['*', '?']) {
+                Some(glob_regex(name)?)
+            } else {
+                None
+            };
+            let matches = match pattern {
+                Some(pattern) => {
+                    let matches = schema
+                        .iter()
+                        .filter(|(_, field)| pattern.is_match(field.name()))
+                        .map(|(qualifier, field)| (qualifier, field.as_ref()))
+                        .collect::<Vec<_>>();
+                    if matches.is_empty() {
+                        let available = schema
+                            .fields()
+                            .iter()
+                            .map(|field| field.name().as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        return Err(PyValueError::new_err(format!(
+                            "Pattern '{name}' matches no columns, available columns: {available}"
+                        )));
+                    }
+                    matches
+                }
+                None => vec![schema.qualified_field_with_unqualified_name(name)?],
+            };
+            for column in matches {
+                if !selected.contains(&column) {
+                    selected.push(column);
+                }
+            }
+        }
+        let columns = selected
+            .into_iter()
+            .map(|column| Expr::Column(Column::from(column)))
+            .collect::<Vec<_>>();
+        let df = self.df.as_ref().clone().select(columns)?;
+        Ok(Self::new(df))
+    }
+
+    /// Select the columns whose type matches any of `include`. Each entry is either a
+    /// pyarrow DataType, matched exactly, or the name of a type family such as
+    /// "integer" or "numeric".
+    fn select_by_dtype(&self, include: Vec<DataTypeSelector>) -> PyResult<Self> {
+        let mut columns = vec![];
+        for (qualifier, field) in self.df.schema().iter() {
+            let mut matched = false;
+            for selector in &include {
+                matched |= selector.matches(field.data_type())?;
+            }
+            if matched {
+                columns.push(Expr::Column(Column::from((qualifier, field.as_ref()))));
+            }
+        }
+        if columns.is_empty() {
+            return Err(PyValueError::new_err(
+                "No columns match the requested data types",
+            ));
+        }
+        let df = self.df.as_ref().clone().select(columns)?;
         Ok(Self::new(df))
     }
 
@@ -114,8 +334,26 @@ impl PyDataFrame {
         Ok(Self::new(df))
     }
 
-    fn filter(&self, predicate: PyExpr) -> PyResult<Self> {
-        let df = self.df.as_ref().clone().filter(predicate.into())?;
+    /// Keep the rows matching all of the `predicates`. Without predicates every row is
+    /// kept.
+    #[pyo3(signature = (*predicates))]
+    fn filter(&self, predicates: Vec<PyExpr>) -> PyResult<Self> {
+        let schema = self.df.schema();
+        for predicate in &predicates {
+            let data_type = predicate.expr.get_type(schema)?;
+            if !matches!(data_type, DataType::Boolean | DataType::Null) {
+                return Err(PyTypeError::new_err(format!(
+                    "Filter predicate {} returns {data_type}, expected a boolean",
+                    predicate.expr
+                )));
+            }
+        }
+
+        let df = self.df.as_ref().clone();
+        let df = match conjunction(predicates.into_iter().map(|p| p.expr)) {
+            Some(predicate) => df.filter(predicate)?,
+            None => df,
+        };
         Ok(Self::new(df))
     }
 
@@ -124,6 +362,107 @@ impl PyDataFrame {
         Ok(Self::new(df))
     }
 
+    /// Cast and reorder the columns to match `schema`, finding each column by name and
+    /// dropping the columns `schema` does not list. With `safe` a value that cannot be
+    /// converted raises an error, otherwise it becomes NULL. A column missing from the
+    /// DataFrame is an error unless `fill_missing` is set, which fills it with NULLs.
+    /// The schema of the result, including its metadata, is `schema`.
+    #[pyo3(signature = (schema, safe=true, fill_missing=false))]
+    fn cast_to_schema(
+        &self,
+        schema: PyArrowType<Schema>,
+        safe: bool,
+        fill_missing: bool,
+    ) -> PyResult<Self> {
+        let input = self.df.schema();
+        let mut exprs = vec![];
+        for field in schema.0.fields() {
+            let expr = match input.qualified_field_with_unqualified_name(field.name()) {
+                Ok(column) => {
+                    let column = Expr::Column(Column::from(column));
+                    if safe {
+                        column.cast_to(field.data_type(), input)?
+                    } else {
+                        Expr::TryCast(TryCast::new(Box::new(column), field.data_type().clone()))
+                    }
+                }
+                Err(_) if fill_missing => lit(ScalarValue::try_from(field.data_type())?),
+                Err(_) => {
+                    return Err(PyValueError::new_err(format!(
+                        "Column '{}' of the target schema is missing",
+                        field.name()
+                    )));
+                }
+            };
+            exprs.push(expr.alias(field.name()));
+        }
+
+        let (state, plan) = self.df.as_ref().clone().into_parts();
+        let output = DFSchema::try_from(schema.0)?;
+        let projection = Projection::try_new_with_schema(exprs, Arc::new(plan), Arc::new(output))?;
+        Ok(Self::new(DataFrame::new(
+            state,
+            LogicalPlan::Projection(projection),
+        )))
+    }
+
+    /// Add or replace several columns in a single projection, each key of `exprs` naming
+    /// the column computed by its expression. A key matching an existing column replaces
+    /// it in place, the other keys are appended in the order of the mapping.
+    fn with_columns_map(&self, exprs: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let new_columns = exprs
+            .iter()
+            .map(|(name, expr)| Ok((name.extract::<String>()?, expr.extract::<PyExpr>()?.expr)))
+            .collect::<PyResult<Vec<_>>>()?;
+        self.project_new_columns(new_columns)
+    }
+
+    /// Add or replace several columns in a single projection, given either as a list of
+    /// `(name, expr)` pairs or as keyword arguments, the pairs first. The columns are
+    /// computed in order, so an expression may refer to a column added or replaced
+    /// earlier in the same call, as with chained `with_column` calls.
+    #[pyo3(signature = (exprs=None, **named))]
+    fn with_columns(
+        &self,
+        exprs: Option<Vec<(String, PyExpr)>>,
+        named: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let mut given = exprs
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, expr)| (name, expr.expr))
+            .collect::<Vec<_>>();
+        if let Some(named) = named {
+            for (name, expr) in named.iter() {
+                given.push((name.extract()?, expr.extract::<PyExpr>()?.expr));
+            }
+        }
+
+        let mut new_columns: Vec<(String, Expr)> = vec![];
+        for (name, expr) in given {
+            if new_columns.iter().any(|(n, _)| *n == name) {
+                return Err(PyValueError::new_err(format!(
+                    "Column '{name}' is given more than once"
+                )));
+            }
+            // inline the columns of this call the expression refers to, so that it can
+            // be evaluated against the input of the projection
+            let expr = expr
+                .transform_up(|e| match &e {
+                    Expr::Column(c) if c.relation.is_none() => {
+                        match new_columns.iter().find(|(n, _)| *n == c.name) {
+                            Some((_, earlier)) => Ok(Transformed::yes(earlier.clone().unalias())),
+                            None => Ok(Transformed::no(e)),
+                        }
+                    }
+                    _ => Ok(Transformed::no(e)),
+                })?
+                .data;
+            new_columns.push((name, expr));
+        }
+        self.project_new_columns(new_columns)
+    }
+
     /// Rename one column by applying a new projection. This is a no-op if the column to be
     /// renamed does not exist.
     fn with_column_renamed(&self, old_name: &str, new_name: &str) -> PyResult<Self> {
@@ -135,6 +474,37 @@ impl PyDataFrame {
         Ok(Self::new(df))
     }
 
+    /// Rename all columns positionally. The number of names must match the number
+    /// of columns in the schema and every name must be unique.
+    fn rename_columns(&self, names: Vec<PyBackedStr>) -> PyResult<Self> {
+        let columns = self.df.schema().columns();
+        if names.len() != columns.len() {
+            return Err(PyValueError::new_err(format!(
+                "Expected {} column names but {} were provided",
+                columns.len(),
+                names.len()
+            )));
+        }
+
+        let mut seen = HashSet::new();
+        for name in &names {
+            if !seen.insert(&**name) {
+                return Err(PyValueError::new_err(format!(
+                    "Duplicate column name '{}'",
+                    &**name
+                )));
+            }
+        }
+
+        let exprs = columns
+            .into_iter()
+            .zip(names.iter())
+            .map(|(column, name)| Expr::Column(column).alias(&**name))
+            .collect();
+        let df = self.df.as_ref().clone().select(exprs)?;
+        Ok(Self::new(df))
+    }
+
     fn aggregate(&self, group_by: Vec<PyExpr>, aggs: Vec<PyExpr>) -> PyResult<Self> {
         let group_by = group_by.into_iter().map(|e| e.into()).collect();
         let aggs = aggs.into_iter().map(|e| e.into()).collect();
@@ -142,9 +512,178 @@ impl PyDataFrame {
         Ok(Self::new(df))
     }
 
+    /// Aggregate into time buckets of width `interval`, e.g. `"1 hour"` or `"15 minutes"`,
+    /// by binning `time_col` with `date_bin` and grouping on the start of each bucket.
+    /// Buckets are aligned to `origin`, or to the Unix epoch when it is not given. The
+    /// result keeps the name `time_col` for the bucket and is sorted by it; buckets
+    /// without any rows are absent, no gap-filling is done.
+    #[pyo3(signature = (time_col, interval, agg_exprs, origin=None))]
+    fn group_by_time(
+        &self,
+        time_col: &str,
+        interval: &str,
+        agg_exprs: Vec<PyExpr>,
+        origin: Option<PyExpr>,
+    ) -> PyResult<Self> {
+        let stride = ScalarValue::try_from_string(
+            interval.to_string(),
+            &DataType::Interval(IntervalUnit::MonthDayNano),
+        )
+        .map_err(|e| PyValueError::new_err(format!("Invalid interval '{interval}': {e}")))?;
+
+        let mut args = vec![lit(stride), col(time_col)];
+        args.extend(origin.map(|e| e.expr));
+        let bucket = datafusion::functions::datetime::date_bin()
+            .call(args)
+            .alias(time_col);
+
+        let aggs = agg_exprs.into_iter().map(|e| e.into()).collect();
+        let df = self
+            .df
+            .as_ref()
+            .clone()
+            .aggregate(vec![bucket], aggs)?
+            .sort(vec![col(time_col).sort(true, true)])?;
+        Ok(Self::new(df))
+    }
+
+    /// Reshape from long to wide: one row per distinct `index_cols` and one column per
+    /// value of `pivot_col`, holding `agg` of `value_col` over the matching rows, or
+    /// NULL where there are none. `agg` is one of sum, mean, min, max or count. The
+    /// pivot values are the distinct non-null values of `pivot_col`, found by running
+    /// a query, unless they are given in `values`.
+    #[pyo3(signature = (index_cols, pivot_col, value_col, agg="sum", values=None))]
+    fn pivot(
+        &self,
+        index_cols: Vec<PyBackedStr>,
+        pivot_col: &str,
+        value_col: &str,
+        agg: &str,
+        values: Option<Vec<LiteralValue>>,
+        py: Python,
+    ) -> PyResult<Self> {
+        let agg_fn: fn(Expr) -> Expr = match agg {
+            "sum" => sum,
+            "mean" => avg,
+            "min" => min,
+            "max" => max,
+            "count" => count,
+            agg => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown pivot aggregate '{agg}', expected one of: sum, mean, min, \
+                     max, count"
+                )))
+            }
+        };
+        let values = match values {
+            Some(values) => values.into_iter().map(|value| value.0).collect(),
+            None => self.distinct_values(pivot_col, py)?,
+        };
+
+        let group_by = index_cols.iter().map(|c| col(&**c)).collect();
+        let values = values
+            .into_iter()
+            .filter(|value| !value.is_null())
+            .collect::<Vec<_>>();
+        let aggs = values
+            .iter()
+            .map(|value| {
+                let matching = when(col(pivot_col).eq(lit(value.clone())), col(value_col)).end()?;
+                Ok(agg_fn(matching).alias(value.to_string()))
+            })
+            .collect::<datafusion_common::Result<Vec<_>>>()?;
+        let mut df = self.df.as_ref().clone().aggregate(group_by, aggs)?;
+        if agg == "count" {
+            // count gives 0 rather than NULL when no row matches
+            let mut exprs = index_cols.iter().map(|c| col(&**c)).collect::<Vec<_>>();
+            exprs.extend(values.iter().map(|value| {
+                let name = value.to_string();
+                let counted = Expr::Column(Column::from_name(name.as_str()));
+                nullif(counted, lit(0_i64)).alias(name)
+            }));
+            df = df.select(exprs)?;
+        }
+        Ok(Self::new(df))
+    }
+
+    /// Reshape from wide to long, the inverse of `pivot`: each row becomes one row per
+    /// column of `value_vars`, holding the `id_vars`, the name of that column as
+    /// `var_name` and its value as `value_name`. NULL values are kept as rows. The
+    /// value columns are cast to their common type.
+    #[pyo3(signature = (id_vars, value_vars, var_name="variable", value_name="value"))]
+    fn unpivot(
+        &self,
+        id_vars: Vec<PyBackedStr>,
+        value_vars: Vec<PyBackedStr>,
+        var_name: &str,
+        value_name: &str,
+    ) -> PyResult<Self> {
+        let schema = self.df.schema();
+        let mut value_type = DataType::Null;
+        for name in &value_vars {
+            let field = schema.field_with_unqualified_name(name)?;
+            value_type = common_value_type(&value_type, field.data_type()).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "unpivot cannot combine column '{}' of type {} with type {value_type}",
+                    &**name,
+                    field.data_type()
+                ))
+            })?;
+        }
+
+        let mut unpivoted: Option<DataFrame> = None;
+        for name in &value_vars {
+            let mut projection = id_vars.iter().map(|c| col(&**c)).collect::<Vec<_>>();
+            projection.push(lit(&**name).alias(var_name));
+            projection.push(cast(col(&**name), value_type.clone()).alias(value_name));
+            let part = self.df.as_ref().clone().select(projection)?;
+            unpivoted = Some(match unpivoted {
+                Some(df) => df.union(part)?,
+                None => part,
+            });
+        }
+        match unpivoted {
+            Some(df) => Ok(Self::new(df)),
+            None => Err(PyValueError::new_err(
+                "unpivot requires at least one value column",
+            )),
+        }
+    }
+
+    /// Keep a random sample of about `fraction` of the rows. Without `seed` each
+    /// execution draws a new sample. With `seed` whether a row is kept is decided by
+    /// hashing its values, so the same seed keeps the same rows on every run and
+    /// machine, and identical rows are either all kept or all dropped.
+    #[pyo3(signature = (fraction, seed=None))]
+    fn sample(&self, fraction: f64, seed: Option<u64>) -> PyResult<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(PyValueError::new_err(format!(
+                "fraction must be between 0 and 1, got {fraction}"
+            )));
+        }
+        let random = match seed {
+            Some(seed) => {
+                let columns = self.df.schema().columns();
+                seeded_random(seed).call(columns.into_iter().map(Expr::Column).collect())
+            }
+            None => datafusion::functions::expr_fn::random(),
+        };
+        let df = self.df.as_ref().clone().filter(random.lt(lit(fraction)))?;
+        Ok(Self::new(df))
+    }
+
+    /// Sort the DataFrame by one or more keys, in order of precedence. Keys built
+    /// with `Expr.sort(...)` keep their direction and null placement, any other
+    /// expression is sorted with the same defaults as `Expr.sort()`.
     #[pyo3(signature = (*exprs))]
     fn sort(&self, exprs: Vec<PyExpr>) -> PyResult<Self> {
-        let exprs = exprs.into_iter().map(|e| e.into()).collect();
+        let exprs = exprs
+            .into_iter()
+            .map(|e| match e.expr {
+                Expr::Sort(_) => e.expr,
+                expr => expr.sort(true, true),
+            })
+            .collect();
         let df = self.df.as_ref().clone().sort(exprs)?;
         Ok(Self::new(df))
     }
@@ -155,16 +694,136 @@ impl PyDataFrame {
         Ok(Self::new(df))
     }
 
+    /// Keep the first `k` rows of each `partition_by` group in `order_by` order, or of
+    /// the whole DataFrame when there is no `partition_by`. Rows tied on `order_by` are
+    /// ranked by the remaining columns so the result does not depend on input order.
+    #[pyo3(signature = (k, order_by, partition_by=None))]
+    fn top_k(
+        &self,
+        k: usize,
+        order_by: Vec<PyExpr>,
+        partition_by: Option<Vec<PyExpr>>,
+    ) -> PyResult<Self> {
+        let mut order_by: Vec<Expr> = order_by
+            .into_iter()
+            .map(|e| match e.expr {
+                Expr::Sort(_) => e.expr,
+                expr => expr.sort(true, true),
+            })
+            .collect();
+        order_by.extend(
+            self.df
+                .schema()
+                .iter()
+                .filter(|(_, field)| !field.data_type().is_nested())
+                .map(|column| Expr::Column(Column::from(column)).sort(true, true)),
+        );
+
+        let df = self.df.as_ref().clone();
+        let partition_by = partition_by.unwrap_or_default();
+        if partition_by.is_empty() {
+            // sort followed by limit is planned as a TopK
+            return Ok(Self::new(df.sort(order_by)?.limit(0, Some(k))?));
+        }
+
+        let row_number = Expr::WindowFunction(WindowFunction {
+            fun: WindowFunctionDefinition::BuiltInWindowFunction(BuiltInWindowFunction::RowNumber),
+            args: vec![],
+            partition_by: partition_by.into_iter().map(|e| e.expr).collect(),
+            order_by,
+            window_frame: WindowFrame::new(Some(true)),
+            null_treatment: None,
+        });
+        let df = df
+            .with_column(TOP_K_ROW_NUMBER, row_number)?
+            .filter(col(TOP_K_ROW_NUMBER).lt_eq(lit(k as u64)))?;
+        Ok(Self::new(drop_column(df, TOP_K_ROW_NUMBER)?))
+    }
+
+    /// Return the first row as a dictionary keyed by column name, or `None` if the
+    /// DataFrame is empty.
+    fn first(&self, py: Python) -> PyResult<Option<PyObject>> {
+        let df = self.df.as_ref().clone().limit(0, Some(1))?;
+        single_row(py, df)
+    }
+
+    /// Return the last row as a dictionary keyed by column name, or `None` if the
+    /// DataFrame is empty. The DataFrame must be sorted, otherwise the last row is
+    /// not deterministic.
+    fn last(&self, py: Python) -> PyResult<Option<PyObject>> {
+        if !is_ordered(self.df.logical_plan()) {
+            return Err(PyValueError::new_err(
+                "last() requires a sorted DataFrame, use sort() to define the row order",
+            ));
+        }
+        let count = wait_for_future(py, self.df.as_ref().clone().count())?;
+        let df = self
+            .df
+            .as_ref()
+            .clone()
+            .limit(count.saturating_sub(1), Some(1))?;
+        single_row(py, df)
+    }
+
     /// Executes the plan, returning a list of `RecordBatch`es.
     /// Unless some order is specified in the plan, there is no
     /// guarantee of the order of the result.
     fn collect(&self, py: Python) -> PyResult<Vec<PyObject>> {
-        let batches = wait_for_future(py, self.df.as_ref().clone().collect())?;
+        let df = self.df.as_ref().clone();
+        let task_ctx = Arc::new(df.task_ctx());
+        let plan = wait_for_future(py, df.create_physical_plan())?;
+        let batches = wait_for_future_interruptible(
+            py,
+            datafusion::physical_plan::collect(plan.clone(), task_ctx),
+        )??;
+        *self.last_execution.lock() = Some(LastExecution {
+            plan,
+            output_rows: batches.iter().map(|b| b.num_rows()).sum(),
+        });
         // cannot use PyResult<Vec<RecordBatch>> return type due to
         // https://github.com/PyO3/pyo3/issues/1813
         batches.into_iter().map(|rb| rb.to_pyarrow(py)).collect()
     }
 
+    /// Returns how effective the pruning of the parquet scans was in the last `collect`
+    /// of this DataFrame, which also backs `to_pydict`, `to_arrow_table` and the other
+    /// conversions, or None if it was not collected yet. The dict holds the row groups
+    /// considered for pruning and the ones pruned by their statistics or bloom filters,
+    /// the rows read by the scans and the rows in the result. Row groups are only
+    /// counted for scans with a predicate, as the others do not prune.
+    fn last_scan_stats(&self, py: Python) -> PyResult<Option<PyObject>> {
+        let last_execution = self.last_execution.lock();
+        let Some(last_execution) = last_execution.as_ref() else {
+            return Ok(None);
+        };
+
+        let mut scans = vec![];
+        collect_parquet_scans(last_execution.plan.as_ref(), &mut scans);
+        let count = |name: &str| {
+            scans
+                .iter()
+                .filter_map(|metrics| metrics.sum_by_name(name))
+                .map(|value| value.as_usize())
+                .sum::<usize>()
+        };
+
+        let stats = PyDict::new_bound(py);
+        stats.set_item(
+            "row_groups_total",
+            count("row_groups_matched_statistics") + count("row_groups_pruned_statistics"),
+        )?;
+        stats.set_item(
+            "row_groups_pruned",
+            count("row_groups_pruned_statistics") + count("row_groups_pruned_bloom_filter"),
+        )?;
+        stats.set_item(
+            "rows_scanned",
+            scans.iter().filter_map(|m| m.output_rows()).sum::<usize>(),
+        )?;
+        stats.set_item("rows_matched", last_execution.output_rows)?;
+        Ok(Some(stats.into()))
+    }
+
     /// Cache DataFrame.
     fn cache(&self, py: Python) -> PyResult<Self> {
         let df = wait_for_future(py, self.df.as_ref().clone().cache())?;
@@ -174,7 +833,8 @@ impl PyDataFrame {
     /// Executes this DataFrame and collects all results into a vector of vector of RecordBatch
     /// maintaining the input partitioning.
     fn collect_partitioned(&self, py: Python) -> PyResult<Vec<Vec<PyObject>>> {
-        let batches = wait_for_future(py, self.df.as_ref().clone().collect_partitioned())?;
+        let batches =
+            wait_for_future_interruptible(py, self.df.as_ref().clone().collect_partitioned())??;
 
         batches
             .into_iter()
@@ -182,11 +842,13 @@ impl PyDataFrame {
             .collect()
     }
 
-    /// Print the result, 20 lines by default
-    #[pyo3(signature = (num=20))]
-    fn show(&self, py: Python, num: usize) -> PyResult<()> {
+    /// Print the first `num` rows. Binary values are shown by their size. With
+    /// `truncate` values longer than `max_width` characters are cut short with an
+    /// ellipsis, or replaced by their size for lists, structs and maps.
+    #[pyo3(signature = (num=20, truncate=true, max_width=80))]
+    fn show(&self, py: Python, num: usize, truncate: bool, max_width: usize) -> PyResult<()> {
         let df = self.df.as_ref().clone().limit(0, Some(num))?;
-        print_dataframe(py, df)
+        print_dataframe(py, df, truncate.then_some(max_width))
     }
 
     /// Filter out duplicate rows
@@ -195,24 +857,70 @@ impl PyDataFrame {
         Ok(Self::new(df))
     }
 
+    /// Keep one row for each distinct value of `on_exprs`, like `DISTINCT ON` in
+    /// PostgreSQL, projecting `select_exprs` (all columns by default). The row kept is
+    /// the first one in the order of `sort_exprs`, which must begin with `on_exprs`.
+    /// Without `sort_exprs` the row kept is not specified.
+    #[pyo3(signature = (on_exprs, select_exprs=None, sort_exprs=None))]
+    fn distinct_on(
+        &self,
+        on_exprs: Vec<PyExpr>,
+        select_exprs: Option<Vec<PyExpr>>,
+        sort_exprs: Option<Vec<PyExpr>>,
+    ) -> PyResult<Self> {
+        let on_exprs = on_exprs.into_iter().map(|e| e.expr).collect();
+        let select_exprs = match select_exprs {
+            Some(exprs) => exprs.into_iter().map(|e| e.expr).collect(),
+            None => self
+                .df
+                .schema()
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .collect(),
+        };
+        let sort_exprs = sort_exprs.map(|exprs| {
+            exprs
+                .into_iter()
+                .map(|e| match e.expr {
+                    Expr::Sort(_) => e.expr,
+                    expr => expr.sort(true, true),
+                })
+                .collect()
+        });
+        let (state, plan) = self.df.as_ref().clone().into_parts();
+        let plan = LogicalPlanBuilder::from(plan)
+            .distinct_on(on_exprs, select_exprs, sort_exprs)?
+            .build()?;
+        Ok(Self::new(DataFrame::new(state, plan)))
+    }
+
+    /// Join with `right` on `join_keys`. With `hint="broadcast"` this join is planned
+    /// as a hash join that collects one side, the smaller one when known, into every
+    /// partition (`mode=CollectLeft`) instead of repartitioning both sides or sorting
+    /// them. The hint applies to this join only, later joins are planned as usual.
+    #[pyo3(signature = (right, join_keys, how, hint=None))]
     fn join(
         &self,
         right: PyDataFrame,
         join_keys: (Vec<PyBackedStr>, Vec<PyBackedStr>),
         how: &str,
+        hint: Option<&str>,
     ) -> PyResult<Self> {
         let join_type = match how {
             "inner" => JoinType::Inner,
             "left" => JoinType::Left,
             "right" => JoinType::Right,
             "full" => JoinType::Full,
-            "semi" => JoinType::LeftSemi,
-            "anti" => JoinType::LeftAnti,
+            "semi" | "left_semi" => JoinType::LeftSemi,
+            "anti" | "left_anti" => JoinType::LeftAnti,
+            "right_semi" => JoinType::RightSemi,
+            "right_anti" => JoinType::RightAnti,
             how => {
-                return Err(DataFusionError::Common(format!(
-                    "The join type {how} does not exist or is not implemented"
-                ))
-                .into());
+                return Err(PyValueError::new_err(format!(
+                    "Unknown join type '{how}', expected one of: inner, left, right, full, \
+                     semi, anti, left_semi, left_anti, right_semi, right_anti"
+                )));
             }
         };
 
@@ -227,6 +935,16 @@ impl PyDataFrame {
             .map(|s| s.as_ref())
             .collect::<Vec<&str>>();
 
+        let broadcast = match hint {
+            None => false,
+            Some("broadcast") => true,
+            Some(hint) => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown join hint '{hint}', expected 'broadcast'"
+                )));
+            }
+        };
+
         let df = self.df.as_ref().clone().join(
             right.df.as_ref().clone(),
             join_type,
@@ -234,18 +952,28 @@ impl PyDataFrame {
             &right_keys,
             None,
         )?;
-        Ok(Self::new(df))
+        if !broadcast {
+            return Ok(Self::new(df));
+        }
+        // only this join is marked, the planning of other joins is left unchanged
+        let (state, plan) = df.into_parts();
+        Ok(Self::new(DataFrame::new(
+            with_broadcast_join_planner(state),
+            broadcast_join(plan),
+        )))
     }
 
-    /// Print the query plan
+    /// Print the query plan. With `verbose` every intermediate plan is shown. With
+    /// `analyze` the plan is executed and each operator is annotated with its runtime
+    /// metrics such as `output_rows` and `elapsed_compute`.
     #[pyo3(signature = (verbose=false, analyze=false))]
     fn explain(&self, py: Python, verbose: bool, analyze: bool) -> PyResult<()> {
         let df = self.df.as_ref().clone().explain(verbose, analyze)?;
-        print_dataframe(py, df)
+        print_dataframe(py, df, None)
     }
 
     /// Get the logical plan for this `DataFrame`
-    fn logical_plan(&self) -> PyResult<PyLogicalPlan> {
+    pub fn logical_plan(&self) -> PyResult<PyLogicalPlan> {
         Ok(self.df.as_ref().clone().logical_plan().clone().into())
     }
 
@@ -260,6 +988,22 @@ impl PyDataFrame {
         Ok(plan.into())
     }
 
+    /// Execute this DataFrame with batches of about `target_batch_size` rows. Scans read
+    /// batches of this size, and the small batches left after a filter, join or
+    /// repartition are combined until they reach it. The data is unchanged.
+    fn coalesce(&self, target_batch_size: usize) -> PyResult<Self> {
+        if target_batch_size == 0 {
+            return Err(PyValueError::new_err(
+                "target_batch_size must be greater than 0",
+            ));
+        }
+        let (mut state, plan) = self.df.as_ref().clone().into_parts();
+        let execution = &mut state.config_mut().options_mut().execution;
+        execution.batch_size = target_batch_size;
+        execution.coalesce_batches = true;
+        Ok(Self::new(DataFrame::new(state, plan)))
+    }
+
     /// Repartition a `DataFrame` based on a logical partitioning scheme.
     fn repartition(&self, num: usize) -> PyResult<Self> {
         let new_df = self
@@ -301,6 +1045,7 @@ impl PyDataFrame {
     /// Calculate the distinct union of two `DataFrame`s.  The
     /// two `DataFrame`s must have exactly the same schema
     fn union_distinct(&self, py_df: PyDataFrame) -> PyResult<Self> {
+        self.check_same_schema(&py_df, "union_distinct")?;
         let new_df = self
             .df
             .as_ref()
@@ -322,6 +1067,7 @@ impl PyDataFrame {
 
     /// Calculate the intersection of two `DataFrame`s.  The two `DataFrame`s must have exactly the same schema
     fn intersect(&self, py_df: PyDataFrame) -> PyResult<Self> {
+        self.check_same_schema(&py_df, "intersect")?;
         let new_df = self
             .df
             .as_ref()
@@ -330,9 +1076,36 @@ impl PyDataFrame {
         Ok(Self::new(new_df))
     }
 
-    /// Calculate the exception of two `DataFrame`s.  The two `DataFrame`s must have exactly the same schema
+    /// Calculate the rows of this `DataFrame` that are also in `py_df`, like SQL
+    /// `INTERSECT ALL`: a row found `m` times here and `n` times in `py_df` is kept
+    /// `min(m, n)` times.  The two `DataFrame`s must have exactly the same schema
+    fn intersect_all(&self, py_df: PyDataFrame) -> PyResult<Self> {
+        self.check_same_schema(&py_df, "intersect_all")?;
+        let new_df = number_copies(self.df.as_ref().clone())?
+            .intersect(number_copies(py_df.df.as_ref().clone())?)?;
+        Ok(Self::new(drop_column(new_df, COPY_NUMBER)?))
+    }
+
+    /// Calculate the exception of two `DataFrame`s, like SQL `EXCEPT ALL`: a row found
+    /// `m` times here and `n` times in `py_df` is kept `max(m - n, 0)` times.  The two
+    /// `DataFrame`s must have exactly the same schema
     fn except_all(&self, py_df: PyDataFrame) -> PyResult<Self> {
-        let new_df = self.df.as_ref().clone().except(py_df.df.as_ref().clone())?;
+        self.check_same_schema(&py_df, "except_all")?;
+        let new_df = number_copies(self.df.as_ref().clone())?
+            .except(number_copies(py_df.df.as_ref().clone())?)?;
+        Ok(Self::new(drop_column(new_df, COPY_NUMBER)?))
+    }
+
+    /// Calculate the distinct rows of this `DataFrame` that are not in `py_df`, like SQL
+    /// `EXCEPT`.  The two `DataFrame`s must have exactly the same schema
+    fn except_distinct(&self, py_df: PyDataFrame) -> PyResult<Self> {
+        self.check_same_schema(&py_df, "except_distinct")?;
+        let new_df = self
+            .df
+            .as_ref()
+            .clone()
+            .distinct()?
+            .except(py_df.df.as_ref().clone())?;
         Ok(Self::new(new_df))
     }
 
@@ -348,19 +1121,23 @@ impl PyDataFrame {
         Ok(())
     }
 
-    /// Write a `DataFrame` to a Parquet file.
+    /// Write a `DataFrame` to a Parquet file. Returns the Parquet files found under
+    /// `path` once the write is done, so several files when `max_rows_per_file` split
+    /// the output. Files that were already there are listed as well.
     #[pyo3(signature = (
         path,
         compression="uncompressed",
-        compression_level=None
+        compression_level=None,
+        max_rows_per_file=None
         ))]
     fn write_parquet(
         &self,
         path: &str,
         compression: &str,
         compression_level: Option<u32>,
+        max_rows_per_file: Option<usize>,
         py: Python,
-    ) -> PyResult<()> {
+    ) -> PyResult<Vec<String>> {
         fn verify_compression_level(cl: Option<u32>) -> Result<u32, PyErr> {
             cl.ok_or(PyValueError::new_err("compression_level is not defined"))
         }
@@ -398,15 +1175,31 @@ impl PyDataFrame {
         let mut options = TableParquetOptions::default();
         options.global.compression = Some(compression_string);
 
-        wait_for_future(
-            py,
-            self.df.as_ref().clone().write_parquet(
-                path,
-                DataFrameWriteOptions::new(),
-                Option::from(options),
-            ),
-        )?;
-        Ok(())
+        let (mut state, plan) = self.df.as_ref().clone().into_parts();
+        if let Some(max_rows) = max_rows_per_file {
+            if max_rows == 0 {
+                return Err(PyValueError::new_err(
+                    "max_rows_per_file must be greater than 0",
+                ));
+            }
+            // a new file is started once a file holds `max_rows` rows, checked after
+            // each batch, so files can exceed the limit by less than a batch
+            state
+                .config_mut()
+                .options_mut()
+                .execution
+                .soft_max_rows_per_output_file = max_rows;
+            // row groups should not span more rows than a file
+            options.global.max_row_group_size = options.global.max_row_group_size.min(max_rows);
+        }
+
+        let df = DataFrame::new(state.clone(), plan);
+        let files = wait_for_future(py, async {
+            df.write_parquet(path, DataFrameWriteOptions::new(), Some(options))
+                .await?;
+            list_files(&state, path, "parquet").await
+        })?;
+        Ok(files)
     }
 
     /// Executes a query and writes the results to a partitioned JSON file.
@@ -421,8 +1214,37 @@ impl PyDataFrame {
         Ok(())
     }
 
+    /// Append the rows of the DataFrame to the registered table `name`, whose provider
+    /// must support inserts like in-memory and listing tables do. The columns are
+    /// matched to the table by position. Returns the number of rows written.
+    fn write_table(&self, name: &str, py: Python) -> PyResult<u64> {
+        let write = self
+            .df
+            .as_ref()
+            .clone()
+            .write_table(name, DataFrameWriteOptions::new());
+        let batches = match wait_for_future(py, write) {
+            Err(datafusion_common::DataFusionError::NotImplemented(message)) => {
+                return Err(PyValueError::new_err(format!(
+                    "Table '{name}' does not support inserts: {message}"
+                )));
+            }
+            result => result?,
+        };
+        let mut written = 0;
+        for batch in &batches {
+            written += as_uint64_array(batch.column(0))?
+                .iter()
+                .flatten()
+                .sum::<u64>();
+        }
+        Ok(written)
+    }
+
     /// Convert to Arrow Table
-    /// Collect the batches and pass to Arrow Table
+    /// Collect the batches and pass to Arrow Table. The batches are exported through
+    /// the Arrow C data interface, so the table shares their buffers and keeps large
+    /// string and large list types as they are
     fn to_arrow_table(&self, py: Python) -> PyResult<PyObject> {
         let batches = self.collect(py)?.to_object(py);
         let schema: PyObject = self.schema().into_py(py);
@@ -464,12 +1286,41 @@ impl PyDataFrame {
 
     /// Convert to pandas dataframe with pyarrow
     /// Collect the batches, pass to Arrow Table & then convert to Pandas DataFrame
-    fn to_pandas(&self, py: Python) -> PyResult<PyObject> {
+    ///
+    /// `types_mapper` is passed on to pyarrow. With `use_nullable_dtypes` integer,
+    /// boolean, float and string columns use the pandas nullable dtypes instead, so
+    /// an integer column with NULLs stays integer rather than becoming float. With
+    /// `self_destruct` the Arrow buffers are released as they are converted, which
+    /// lowers the peak memory use.
+    #[pyo3(signature = (types_mapper=None, use_nullable_dtypes=false, self_destruct=false))]
+    fn to_pandas(
+        &self,
+        types_mapper: Option<PyObject>,
+        use_nullable_dtypes: bool,
+        self_destruct: bool,
+        py: Python,
+    ) -> PyResult<PyObject> {
+        if types_mapper.is_some() && use_nullable_dtypes {
+            return Err(PyValueError::new_err(
+                "Only one of types_mapper and use_nullable_dtypes can be given",
+            ));
+        }
         let table = self.to_arrow_table(py)?;
 
         Python::with_gil(|py| {
+            let kwargs = PyDict::new_bound(py);
+            if let Some(types_mapper) = types_mapper {
+                kwargs.set_item("types_mapper", types_mapper)?;
+            }
+            if use_nullable_dtypes {
+                kwargs.set_item("types_mapper", nullable_dtypes_mapper(py)?)?;
+            }
+            if self_destruct {
+                kwargs.set_item("split_blocks", true)?;
+                kwargs.set_item("self_destruct", true)?;
+            }
             // See also: https://arrow.apache.org/docs/python/generated/pyarrow.Table.html#pyarrow.Table.to_pandas
-            let result = table.call_method0(py, "to_pandas")?;
+            let result = table.call_method_bound(py, "to_pandas", (), Some(&kwargs))?;
             Ok(result)
         })
     }
@@ -499,28 +1350,388 @@ impl PyDataFrame {
     }
 
     /// Convert to polars dataframe with pyarrow
-    /// Collect the batches, pass to Arrow Table & then convert to polars DataFrame
+    /// Collect the batches, pass to Arrow Table & then convert to polars DataFrame.
+    /// The chunks are kept as they are so polars can reuse the Arrow buffers
     fn to_polars(&self, py: Python) -> PyResult<PyObject> {
         let table = self.to_arrow_table(py)?;
 
         Python::with_gil(|py| {
-            let dataframe = py.import_bound("polars")?.getattr("DataFrame")?;
-            let args = PyTuple::new_bound(py, &[table]);
-            let result: PyObject = dataframe.call1(args)?.into();
-            Ok(result)
+            // See also: https://docs.pola.rs/api/python/stable/reference/api/polars.from_arrow.html
+            let kwargs = PyDict::new_bound(py);
+            kwargs.set_item("rechunk", false)?;
+            let result =
+                py.import_bound("polars")?
+                    .call_method("from_arrow", (table,), Some(&kwargs))?;
+            Ok(result.into())
         })
     }
 
-    // Executes this DataFrame to get the total number of rows.
+    /// Returns the number of rows by executing a `COUNT(*)` aggregate over the plan,
+    /// so columns that do not affect the row count are never computed.
     fn count(&self, py: Python) -> PyResult<usize> {
-        Ok(wait_for_future(py, self.df.as_ref().clone().count())?)
+        Ok(wait_for_future_interruptible(
+            py,
+            self.df.as_ref().clone().count(),
+        )??)
+    }
+
+    /// Returns an estimate of the memory taken by the result in bytes, from the
+    /// statistics of the physical plan without executing it, or None if the plan has
+    /// no size estimate. Only the columns the DataFrame produces are counted, so the
+    /// estimate shrinks when columns are dropped. It is an estimate: filters, joins
+    /// and variable width columns can make the actual size differ considerably.
+    fn estimated_bytes(&self, py: Python) -> PyResult<Option<usize>> {
+        let plan = wait_for_future(py, self.df.as_ref().clone().create_physical_plan())?;
+        Ok(plan.statistics()?.total_byte_size.get_value().copied())
+    }
+
+    /// Returns the number of nulls in each column as a dict keyed by column name. With
+    /// `exact=False` the counts are read from the statistics of the physical plan, e.g.
+    /// parquet metadata when `datafusion.execution.collect_statistics` is enabled,
+    /// without scanning the data; they may be estimates after filters. Columns without
+    /// statistics, and all columns with `exact=True`, are counted by a single
+    /// aggregate over the data.
+    #[pyo3(signature = (exact=false))]
+    fn null_counts(&self, exact: bool, py: Python) -> PyResult<PyObject> {
+        let columns = self.df.schema().columns();
+        let mut counts: Vec<Option<usize>> = vec![None; columns.len()];
+        if !exact {
+            let plan = wait_for_future(py, self.df.as_ref().clone().create_physical_plan())?;
+            let statistics = plan.statistics()?;
+            for (count, stats) in counts.iter_mut().zip(&statistics.column_statistics) {
+                *count = stats.null_count.get_value().copied();
+            }
+        }
+
+        let missing = columns
+            .iter()
+            .zip(&counts)
+            .filter(|(_, count)| count.is_none())
+            .map(|(column, _)| column.clone())
+            .collect::<Vec<_>>();
+        let mut exact_counts = self.exact_null_counts(&missing, py)?.into_iter();
+        let result = PyDict::new_bound(py);
+        for (column, count) in columns.iter().zip(counts) {
+            let count = match count {
+                Some(count) => count,
+                None => exact_counts.next().unwrap_or_default(),
+            };
+            result.set_item(&column.name, count)?;
+        }
+        Ok(result.into())
+    }
+
+    /// Raises a ValueError if the values of `columns` do not identify each row, listing
+    /// up to five keys found in more than one row with their number of rows. NULL
+    /// values are compared like any other value. The keys are counted by a single
+    /// aggregate over the data.
+    fn assert_unique(&self, columns: Vec<PyBackedStr>, py: Python) -> PyResult<()> {
+        if columns.is_empty() {
+            return Err(PyValueError::new_err(
+                "assert_unique requires at least one column",
+            ));
+        }
+        let schema = self.df.schema();
+        let keys = columns
+            .iter()
+            .map(|name| {
+                schema
+                    .qualified_field_with_unqualified_name(name)
+                    .map(|field| Expr::Column(Column::from(field)))
+            })
+            .collect::<datafusion_common::Result<Vec<_>>>()?;
+
+        let df = self
+            .df
+            .as_ref()
+            .clone()
+            .aggregate(keys, vec![datafusion_expr::count(lit(1)).alias(KEY_COUNT)])?
+            .filter(col(KEY_COUNT).gt(lit(1)))?
+            .limit(0, Some(5))?;
+        let batches = wait_for_future(py, df.collect())?;
+
+        let options = FormatOptions::default().with_null("NULL");
+        let mut duplicates = vec![];
+        for batch in &batches {
+            let values = batch
+                .columns()
+                .iter()
+                .map(|array| ArrayFormatter::try_new(array.as_ref(), &options))
+                .collect::<Result<Vec<_>, ArrowError>>()
+                .map_err(DataFusionError::ArrowError)?;
+            let (key_values, count) = values.split_at(columns.len());
+            for row in 0..batch.num_rows() {
+                let key = columns
+                    .iter()
+                    .zip(key_values)
+                    .map(|(name, values)| format!("{}={}", &**name, values.value(row)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                duplicates.push(format!("({key}) in {} rows", count[0].value(row)));
+            }
+        }
+        if !duplicates.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "Found duplicate keys: {}",
+                duplicates.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Raises a ValueError listing the null count of each offending column if any of
+    /// `columns` (all columns by default) contains nulls. All null counts are computed
+    /// by a single aggregate over the data.
+    #[pyo3(signature = (columns=None))]
+    fn assert_no_nulls(&self, columns: Option<Vec<PyBackedStr>>, py: Python) -> PyResult<()> {
+        let schema = self.df.schema();
+        let columns = match columns {
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    schema
+                        .qualified_field_with_unqualified_name(name)
+                        .map(Column::from)
+                })
+                .collect::<datafusion_common::Result<Vec<_>>>()?,
+            None => schema.columns(),
+        };
+        if columns.is_empty() {
+            return Ok(());
+        }
+
+        let offending = columns
+            .iter()
+            .zip(self.exact_null_counts(&columns, py)?)
+            .filter(|(_, count)| *count > 0)
+            .map(|(column, count)| format!("{} ({count})", column.name))
+            .collect::<Vec<_>>();
+        if !offending.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "Found null values in columns: {}",
+                offending.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn name_regex(pattern: &str) -> PyResult<Regex> {
+    Regex::new(pattern)
+        .map_err(|e| PyValueError::new_err(format!("Invalid column pattern '{pattern}': {e}")))
+}
+
+/// Translates a glob pattern, where `*` matches any characters and `?` a single one,
+/// into a regular expression matching whole column names
+fn glob_regex(pattern: &str) -> PyResult<Regex> {
+    let mut translated = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => translated.push_str(".*"),
+            '?' => translated.push('.'),
+            c => translated.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    translated.push('$');
+    name_regex(&translated)
+}
+
+/// Gather the metrics of the parquet scans in `plan`
+fn collect_parquet_scans(plan: &dyn ExecutionPlan, scans: &mut Vec<MetricsSet>) {
+    if plan.as_any().is::<ParquetExec>() {
+        scans.extend(plan.metrics());
+    }
+    for child in plan.children() {
+        collect_parquet_scans(child.as_ref(), scans);
+    }
+}
+
+/// Returns a line-based diff of the optimized logical plans of two DataFrames. Lines
+/// only in `a` are prefixed with "- ", lines only in `b` with "+ " and shared lines
+/// with "  ". Identical plans produce an empty string.
+#[pyfunction]
+pub fn plan_diff(a: PyDataFrame, b: PyDataFrame) -> PyResult<String> {
+    let a = a.df.as_ref().clone().into_optimized_plan()?;
+    let b = b.df.as_ref().clone().into_optimized_plan()?;
+    let a = format!("{}", a.display_indent());
+    let b = format!("{}", b.display_indent());
+    if a == b {
+        return Ok(String::new());
+    }
+    Ok(diff_lines(
+        &a.lines().collect::<Vec<_>>(),
+        &b.lines().collect::<Vec<_>>(),
+    )
+    .join("\n"))
+}
+
+/// Diffs two lists of lines using their longest common subsequence
+fn diff_lines(a: &[&str], b: &[&str]) -> Vec<String> {
+    // lcs[i][j] is the length of the longest common subsequence of a[i..] and b[j..]
+    let mut lcs = vec![vec![0_usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            diff.push(format!("  {}", a[i]));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(format!("- {}", a[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+ {}", b[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// A data type to select columns by, see `PyDataFrame::select_by_dtype`
+#[derive(FromPyObject)]
+enum DataTypeSelector {
+    Exact(PyArrowType<DataType>),
+    Family(String),
+}
+
+impl DataTypeSelector {
+    fn matches(&self, data_type: &DataType) -> PyResult<bool> {
+        match self {
+            Self::Exact(expected) => Ok(&expected.0 == data_type),
+            Self::Family(family) => is_type_family(family, data_type),
+        }
+    }
+}
+
+/// Lists the files with `extension` under `path`, sorted. Local files are returned
+/// as plain paths and other files as URLs
+async fn list_files(
+    state: &SessionState,
+    path: &str,
+    extension: &str,
+) -> datafusion_common::Result<Vec<String>> {
+    let url = ListingTableUrl::parse(path)?;
+    let store = state.runtime_env().object_store(&url)?;
+    let mut files: Vec<String> = url
+        .list_all_files(state, store.as_ref(), extension)
+        .await?
+        .map_ok(|meta| match url.scheme() {
+            "file" => format!("/{}", meta.location),
+            _ => format!("{}{}", url.object_store().as_str(), meta.location),
+        })
+        .try_collect()
+        .await?;
+    files.sort();
+    Ok(files)
+}
+
+fn is_type_family(family: &str, data_type: &DataType) -> PyResult<bool> {
+    let matches = match family {
+        "integer" => data_type.is_integer(),
+        "signed_integer" => data_type.is_signed_integer(),
+        "unsigned_integer" => data_type.is_unsigned_integer(),
+        "floating" => data_type.is_floating(),
+        "decimal" => matches!(
+            data_type,
+            DataType::Decimal128(_, _) | DataType::Decimal256(_, _)
+        ),
+        "numeric" => data_type.is_numeric(),
+        "string" => matches!(
+            data_type,
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+        ),
+        "binary" => matches!(
+            data_type,
+            DataType::Binary
+                | DataType::LargeBinary
+                | DataType::BinaryView
+                | DataType::FixedSizeBinary(_)
+        ),
+        "boolean" => data_type == &DataType::Boolean,
+        "temporal" => data_type.is_temporal(),
+        "nested" => data_type.is_nested(),
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown data type family '{family}', expected one of integer, signed_integer, \
+                 unsigned_integer, floating, decimal, numeric, string, binary, boolean, \
+                 temporal or nested"
+            )))
+        }
+    };
+    Ok(matches)
+}
+
+/// Remove the helper column `name` from `df`
+fn drop_column(df: DataFrame, name: &str) -> datafusion_common::Result<DataFrame> {
+    let columns = df
+        .schema()
+        .columns()
+        .into_iter()
+        .filter(|column| column.name != name)
+        .map(Expr::Column)
+        .collect();
+    df.select(columns)
+}
+
+/// Number the copies of each distinct row of `df` in a `COPY_NUMBER` column, so that
+/// the semi and anti joins of `intersect` and `except` match the nth copy of a row
+/// only with the nth copy on the other side
+fn number_copies(df: DataFrame) -> PyResult<DataFrame> {
+    let columns = df
+        .schema()
+        .columns()
+        .into_iter()
+        .map(Expr::Column)
+        .collect();
+    let copy_number = Expr::WindowFunction(WindowFunction {
+        fun: WindowFunctionDefinition::BuiltInWindowFunction(BuiltInWindowFunction::RowNumber),
+        args: vec![],
+        partition_by: columns,
+        order_by: vec![],
+        window_frame: WindowFrame::new(None),
+        null_treatment: None,
+    });
+    Ok(df.with_column(COPY_NUMBER, copy_number)?)
+}
+
+/// Collect a DataFrame of at most one row into a dictionary keyed by column name
+fn single_row(py: Python, df: DataFrame) -> PyResult<Option<PyObject>> {
+    let rows = PyDataFrame::new(df).to_pylist(py)?;
+    let rows = rows.bind(py).downcast::<PyList>()?;
+    Ok(rows.iter().next().map(|row| row.unbind()))
+}
+
+/// Whether the rows produced by a plan are in a deterministic order, i.e. the plan
+/// is a sort optionally followed by operators that preserve the order of their input.
+fn is_ordered(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Sort(_) => true,
+        LogicalPlan::Projection(_)
+        | LogicalPlan::Filter(_)
+        | LogicalPlan::Limit(_)
+        | LogicalPlan::SubqueryAlias(_) => plan.inputs().into_iter().all(is_ordered),
+        _ => false,
     }
 }
 
 /// Print DataFrame
-fn print_dataframe(py: Python, df: DataFrame) -> PyResult<()> {
+fn print_dataframe(py: Python, df: DataFrame, max_width: Option<usize>) -> PyResult<()> {
     // Get string representation of record batches
-    let batches = wait_for_future(py, df.collect())?;
+    let batches = wait_for_future(py, df.collect())?
+        .iter()
+        .map(|batch| display_batch(batch, max_width))
+        .collect::<Result<Vec<_>, ArrowError>>()
+        .map_err(DataFusionError::ArrowError)?;
     let batches_as_string = pretty::pretty_format_batches(&batches);
     let result = match batches_as_string {
         Ok(batch) => format!("DataFrame()\n{batch}"),
@@ -533,3 +1744,93 @@ fn print_dataframe(py: Python, df: DataFrame) -> PyResult<()> {
     print.call1((result,))?;
     Ok(())
 }
+
+/// Convert every column of `batch` to strings for display. Binary values are shown by
+/// their size and with `max_width` longer values are truncated, except for nested
+/// values which are shown by their size instead
+fn display_batch(batch: &RecordBatch, max_width: Option<usize>) -> Result<RecordBatch, ArrowError> {
+    let options = FormatOptions::default();
+    let mut fields = vec![];
+    let mut columns: Vec<ArrayRef> = vec![];
+    for (field, array) in batch.schema().fields().iter().zip(batch.columns()) {
+        let formatter = ArrayFormatter::try_new(array.as_ref(), &options)?;
+        let mut values = StringBuilder::with_capacity(array.len(), 0);
+        for i in 0..array.len() {
+            if array.is_null(i) {
+                values.append_null();
+                continue;
+            }
+            let value = match binary_size(array, i) {
+                Some(size) => format!("<{size} bytes>"),
+                None => formatter.value(i).try_to_string()?,
+            };
+            match max_width {
+                Some(width) if value.chars().count() > width => match nested_size(array, i) {
+                    Some(size) => values.append_value(size),
+                    None => {
+                        let kept: String = value.chars().take(width.saturating_sub(1)).collect();
+                        values.append_value(format!("{kept}…"));
+                    }
+                },
+                _ => values.append_value(value),
+            }
+        }
+        fields.push(Field::new(field.name(), DataType::Utf8, true));
+        columns.push(Arc::new(values.finish()));
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+}
+
+/// The number of bytes of a binary value
+fn binary_size(array: &ArrayRef, i: usize) -> Option<usize> {
+    match array.data_type() {
+        DataType::Binary => Some(array.as_binary::<i32>().value(i).len()),
+        DataType::LargeBinary => Some(array.as_binary::<i64>().value(i).len()),
+        DataType::FixedSizeBinary(size) => Some(*size as usize),
+        _ => None,
+    }
+}
+
+/// A description of the size of a nested value, shown when it is too wide to print
+fn nested_size(array: &ArrayRef, i: usize) -> Option<String> {
+    let items = |n: usize| format!("<list of {n} items>");
+    match array.data_type() {
+        DataType::List(_) => Some(items(array.as_list::<i32>().value(i).len())),
+        DataType::LargeList(_) => Some(items(array.as_list::<i64>().value(i).len())),
+        DataType::FixedSizeList(_, size) => Some(items(*size as usize)),
+        DataType::Struct(fields) => Some(format!("<struct with {} fields>", fields.len())),
+        DataType::Map(_, _) => Some(format!(
+            "<map of {} entries>",
+            array.as_map().value(i).len()
+        )),
+        _ => None,
+    }
+}
+
+/// A pyarrow `types_mapper` from the Arrow types that pandas has nullable dtypes for
+fn nullable_dtypes_mapper(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    let pa = py.import_bound("pyarrow")?;
+    let pd = py.import_bound("pandas")?;
+    let mapping = PyDict::new_bound(py);
+    for (arrow_type, dtype) in [
+        ("int8", "Int8Dtype"),
+        ("int16", "Int16Dtype"),
+        ("int32", "Int32Dtype"),
+        ("int64", "Int64Dtype"),
+        ("uint8", "UInt8Dtype"),
+        ("uint16", "UInt16Dtype"),
+        ("uint32", "UInt32Dtype"),
+        ("uint64", "UInt64Dtype"),
+        ("bool_", "BooleanDtype"),
+        ("float32", "Float32Dtype"),
+        ("float64", "Float64Dtype"),
+        ("string", "StringDtype"),
+        ("large_string", "StringDtype"),
+    ] {
+        mapping.set_item(
+            pa.getattr(arrow_type)?.call0()?,
+            pd.getattr(dtype)?.call0()?,
+        )?;
+    }
+    mapping.getattr("get")
+}
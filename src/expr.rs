@@ -17,28 +17,33 @@
 
 use datafusion_expr::utils::exprlist_to_fields;
 use datafusion_expr::LogicalPlan;
-use pyo3::{basic::CompareOp, prelude::*};
+use pyo3::{basic::CompareOp, exceptions::PyValueError, prelude::*};
 use std::convert::{From, Into};
 use std::sync::Arc;
 
 use arrow::pyarrow::ToPyArrow;
-use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use datafusion::arrow::pyarrow::PyArrowType;
-use datafusion::functions::core::expr_ext::FieldAccessor;
 use datafusion::scalar::ScalarValue;
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
+use datafusion_common::DFSchema;
 use datafusion_expr::{
-    col,
+    binary_expr, col,
     expr::{AggregateFunction, InList, InSubquery, ScalarFunction, Sort, WindowFunction},
-    lit, Between, BinaryExpr, Case, Cast, Expr, Like, Operator, TryCast,
+    lit, Between, BinaryExpr, Case, Cast, Expr, ExprSchemable, Like, Operator, Subquery, TryCast,
 };
+use datafusion_functions_array::expr_ext::IndexAccessor;
 
 use crate::common::data_type::{DataTypeMap, RexType};
+use crate::dataframe::PyDataFrame;
 use crate::errors::{py_runtime_err, py_type_err, DataFusionError};
 use crate::expr::aggregate_expr::PyAggregateFunction;
 use crate::expr::binary_expr::PyBinaryExpr;
 use crate::expr::column::PyColumn;
 use crate::expr::literal::PyLiteral;
+use crate::functions::{fill_null, get_field};
 use crate::sql::logical::PyLogicalPlan;
+use crate::utils::LiteralValue;
 
 use self::alias::PyAlias;
 use self::bool_expr::{
@@ -110,11 +115,64 @@ impl From<Expr> for PyExpr {
     }
 }
 
+/// Rewrite a LIKE pattern escaped with `escape` to use backslash escapes
+fn escape_like_pattern(pattern: &str, escape: char) -> PyResult<String> {
+    let mut escaped = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == escape {
+            let Some(next) = chars.next() else {
+                return Err(PyValueError::new_err(format!(
+                    "LIKE pattern '{pattern}' ends with the escape character '{escape}'"
+                )));
+            };
+            escaped.push('\\');
+            escaped.push(next);
+        } else if c == '\\' {
+            escaped.push_str("\\\\");
+        } else {
+            escaped.push(c);
+        }
+    }
+    Ok(escaped)
+}
+
 /// Convert a list of DataFusion Expr to PyExpr
 pub fn py_expr_list(expr: &[Expr]) -> PyResult<Vec<PyExpr>> {
     Ok(expr.iter().map(|e| PyExpr::from(e.clone())).collect())
 }
 
+/// The key passed to `PyExpr.__getitem__`
+#[derive(FromPyObject)]
+pub enum ItemKey {
+    Index(i64),
+    Name(String),
+}
+
+/// The target of `PyExpr.cast_to`: a type, or the name of a column to take it from
+#[derive(FromPyObject)]
+pub enum CastTarget {
+    Type(PyArrowType<DataType>),
+    Column(String),
+}
+
+/// An expression, or a Python value used as a literal
+#[derive(FromPyObject)]
+#[allow(clippy::large_enum_variant)]
+pub enum ExprOrLiteral {
+    Expr(PyExpr),
+    Literal(LiteralValue),
+}
+
+impl From<ExprOrLiteral> for Expr {
+    fn from(value: ExprOrLiteral) -> Expr {
+        match value {
+            ExprOrLiteral::Expr(expr) => expr.expr,
+            ExprOrLiteral::Literal(value) => lit(value.0),
+        }
+    }
+}
+
 #[pymethods]
 impl PyExpr {
     /// Return the specific expression
@@ -165,6 +223,39 @@ impl PyExpr {
         Ok(self.expr.variant_name())
     }
 
+    /// Returns the direct sub-expressions of this expression, in order. For a
+    /// BinaryExpr these are its left and right operands, for a function call its
+    /// arguments.
+    fn children(&self) -> PyResult<Vec<PyExpr>> {
+        let mut children = vec![];
+        self.expr.apply_children(|child| {
+            children.push(PyExpr::from(child.clone()));
+            Ok(TreeNodeRecursion::Continue)
+        })?;
+        Ok(children)
+    }
+
+    /// Returns a copy of this expression with its direct sub-expressions replaced by
+    /// `children`, which must have as many entries as `children()` returns, so
+    /// `expr.with_children(expr.children())` is equal to `expr`.
+    fn with_children(&self, children: Vec<PyExpr>) -> PyResult<PyExpr> {
+        let expected = self.children()?.len();
+        if children.len() != expected {
+            return Err(PyValueError::new_err(format!(
+                "{} has {expected} children, got {}",
+                self.expr.variant_name(),
+                children.len()
+            )));
+        }
+        let mut children = children.into_iter().map(|c| c.expr);
+        let expr = self
+            .expr
+            .clone()
+            .map_children(|_| Ok(Transformed::yes(children.next().unwrap())))?
+            .data;
+        Ok(expr.into())
+    }
+
     fn __richcmp__(&self, other: PyExpr, op: CompareOp) -> PyExpr {
         let expr = match op {
             CompareOp::Lt => self.expr.clone().lt(other.expr),
@@ -215,8 +306,25 @@ impl PyExpr {
         Ok(expr.into())
     }
 
-    fn __getitem__(&self, key: &str) -> PyResult<PyExpr> {
-        Ok(self.expr.clone().field(key).into())
+    /// `expr["name"]` reads a struct field like `get_field`, `expr[i]` reads the list
+    /// element at index `i`, counting from 0, or from the end if `i` is negative.
+    /// Indexes out of range give NULL.
+    fn __getitem__(&self, key: ItemKey) -> PyExpr {
+        match key {
+            ItemKey::Name(name) => self.get_field(&name),
+            ItemKey::Index(i) => {
+                let position = if i >= 0 { i + 1 } else { i };
+                self.expr.clone().index(lit(position)).into()
+            }
+        }
+    }
+
+    /// Read the field `name` of a struct. A field the struct does not have is an error
+    /// when the expression is planned, listing the fields that are available.
+    pub fn get_field(&self, name: &str) -> PyExpr {
+        get_field::get_field()
+            .call(vec![self.expr.clone(), lit(name)])
+            .into()
     }
 
     #[staticmethod]
@@ -240,6 +348,85 @@ impl PyExpr {
         self.expr.clone().sort(ascending, nulls_first).into()
     }
 
+    /// Create an `expr IN (subquery)` expression, the subquery must return a single column
+    #[pyo3(signature = (subquery, negated=false))]
+    pub fn in_subquery(&self, subquery: PyDataFrame, negated: bool) -> PyResult<PyExpr> {
+        let subquery = subquery_from_dataframe(&subquery)?;
+        Ok(Expr::InSubquery(InSubquery::new(
+            Box::new(self.expr.clone()),
+            subquery,
+            negated,
+        ))
+        .into())
+    }
+
+    /// Replace NULLs with `value`, a literal or another expression. Numeric types are
+    /// widened to a common type, other mismatched types fail when the plan is built
+    pub fn fill_null(&self, value: ExprOrLiteral) -> PyExpr {
+        fill_null::fill_null()
+            .call(vec![self.expr.clone(), value.into()])
+            .into()
+    }
+
+    /// Null-safe inequality: true if the operands differ, counting NULL as a value
+    /// of its own, so the result is never NULL.
+    pub fn is_distinct_from(&self, other: ExprOrLiteral) -> PyExpr {
+        binary_expr(self.expr.clone(), Operator::IsDistinctFrom, other.into()).into()
+    }
+
+    /// Null-safe equality: true if the operands are equal or both NULL, so the result
+    /// is never NULL.
+    pub fn is_not_distinct_from(&self, other: ExprOrLiteral) -> PyExpr {
+        binary_expr(self.expr.clone(), Operator::IsNotDistinctFrom, other.into()).into()
+    }
+
+    /// Match against a LIKE pattern, where `%` matches any sequence of characters and
+    /// `_` any single character. `escape` is the character making the next `%`, `_` or
+    /// itself match literally, as with SQL `ESCAPE`; it defaults to a backslash, any
+    /// other escape character requires the pattern to be a string literal.
+    #[pyo3(signature = (pattern, escape=None))]
+    pub fn like(&self, pattern: ExprOrLiteral, escape: Option<char>) -> PyResult<PyExpr> {
+        self.like_expr(pattern, escape, false, false)
+    }
+
+    /// The negation of `like`
+    #[pyo3(signature = (pattern, escape=None))]
+    pub fn not_like(&self, pattern: ExprOrLiteral, escape: Option<char>) -> PyResult<PyExpr> {
+        self.like_expr(pattern, escape, true, false)
+    }
+
+    /// Match against a LIKE pattern ignoring case, see `like`
+    #[pyo3(signature = (pattern, escape=None))]
+    pub fn ilike(&self, pattern: ExprOrLiteral, escape: Option<char>) -> PyResult<PyExpr> {
+        self.like_expr(pattern, escape, false, true)
+    }
+
+    /// The negation of `ilike`
+    #[pyo3(signature = (pattern, escape=None))]
+    pub fn not_ilike(&self, pattern: ExprOrLiteral, escape: Option<char>) -> PyResult<PyExpr> {
+        self.like_expr(pattern, escape, true, true)
+    }
+
+    /// Match against a SQL `SIMILAR TO` pattern. The pattern is evaluated as a regular
+    /// expression searched for in the value, use `^` and `$` to match the whole value.
+    /// With `case_insensitive` the case of letters is ignored.
+    #[pyo3(signature = (pattern, case_insensitive=false, negated=false))]
+    pub fn similar_to(
+        &self,
+        pattern: ExprOrLiteral,
+        case_insensitive: bool,
+        negated: bool,
+    ) -> PyExpr {
+        Expr::SimilarTo(Like::new(
+            negated,
+            Box::new(self.expr.clone()),
+            Box::new(pattern.into()),
+            None,
+            case_insensitive,
+        ))
+        .into()
+    }
+
     pub fn is_null(&self) -> PyExpr {
         self.expr.clone().is_null().into()
     }
@@ -251,6 +438,31 @@ impl PyExpr {
         expr.into()
     }
 
+    /// Cast to `target`, either a type or the name of a column of `schema` whose type
+    /// is used. Unlike `cast`, the cast is checked against `schema` right away, so an
+    /// unsupported cast raises here rather than when the query runs.
+    pub fn cast_to(&self, target: CastTarget, schema: PyArrowType<Schema>) -> PyResult<PyExpr> {
+        let schema = DFSchema::try_from(schema.0)?;
+        let to = match target {
+            CastTarget::Type(data_type) => data_type.0,
+            CastTarget::Column(name) => match schema.field_with_unqualified_name(&name) {
+                Ok(field) => field.data_type().clone(),
+                Err(_) => {
+                    let available = schema
+                        .fields()
+                        .iter()
+                        .map(|f| f.name().as_str())
+                        .collect::<Vec<_>>();
+                    return Err(PyValueError::new_err(format!(
+                        "Column '{name}' is not in the schema, available fields: {}",
+                        available.join(", ")
+                    )));
+                }
+            },
+        };
+        Ok(self.expr.clone().cast_to(&to, &schema)?.into())
+    }
+
     /// A Rex (Row Expression) specifies a single row of data. That specification
     /// could include user defined functions or types. RexType identifies the row
     /// as one of the possible valid `RexTypes`.
@@ -476,6 +688,39 @@ impl PyExpr {
 }
 
 impl PyExpr {
+    fn like_expr(
+        &self,
+        pattern: ExprOrLiteral,
+        escape: Option<char>,
+        negated: bool,
+        case_insensitive: bool,
+    ) -> PyResult<PyExpr> {
+        let pattern: Expr = pattern.into();
+        // The engine only understands backslash escapes, so a pattern with another
+        // escape character is rewritten to use backslashes
+        let pattern = match (escape, pattern) {
+            (None | Some('\\'), pattern) => pattern,
+            (
+                Some(escape),
+                Expr::Literal(ScalarValue::Utf8(Some(p)) | ScalarValue::LargeUtf8(Some(p))),
+            ) => lit(escape_like_pattern(&p, escape)?),
+            (Some(escape), pattern) => {
+                let message = format!(
+                    "The escape character '{escape}' requires a literal pattern, got {pattern}"
+                );
+                return Err(PyValueError::new_err(message));
+            }
+        };
+        Ok(Expr::Like(Like::new(
+            negated,
+            Box::new(self.expr.clone()),
+            Box::new(pattern),
+            None,
+            case_insensitive,
+        ))
+        .into())
+    }
+
     pub fn _column_name(&self, plan: &LogicalPlan) -> Result<String, DataFusionError> {
         let field = Self::expr_to_field(&self.expr, plan)?;
         Ok(field.name().to_owned())
@@ -497,8 +742,8 @@ impl PyExpr {
                 Ok(Arc::new(input_plan.schema().field(0).clone()))
             }
             _ => {
-                let fields =
-                    exprlist_to_fields(&[expr.clone()], input_plan).map_err(PyErr::from)?;
+                let fields = exprlist_to_fields(std::slice::from_ref(expr), input_plan)
+                    .map_err(PyErr::from)?;
                 Ok(fields[0].1.clone())
             }
         }
@@ -552,6 +797,17 @@ impl PyExpr {
     }
 }
 
+/// Wraps the logical plan of a DataFrame as a subquery. Outer columns referenced by the
+/// plan are collected so correlated subqueries can be resolved against the outer query.
+pub(crate) fn subquery_from_dataframe(df: &PyDataFrame) -> PyResult<Subquery> {
+    let plan = df.logical_plan()?.plan();
+    let outer_ref_columns = plan.all_out_ref_exprs();
+    Ok(Subquery {
+        subquery: plan,
+        outer_ref_columns,
+    })
+}
+
 /// Initializes the `expr` module to match the pattern of `datafusion-expr` https://docs.rs/datafusion-expr/latest/datafusion_expr/
 pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyExpr>()?;
@@ -73,6 +73,14 @@ impl From<Window> for PyWindow {
     }
 }
 
+/// A bound passed to `WindowFrame`: a `WindowFrameBound`, or an offset where None stands
+/// for an unbounded frame
+#[derive(FromPyObject)]
+pub enum FrameBoundArg {
+    Bound(PyWindowFrameBound),
+    Offset(Option<u64>),
+}
+
 impl From<WindowFrameBound> for PyWindowFrameBound {
     fn from(frame_bound: WindowFrameBound) -> Self {
         PyWindowFrameBound { frame_bound }
@@ -166,9 +174,12 @@ fn not_window_function_err(expr: Expr) -> PyErr {
 
 #[pymethods]
 impl PyWindowFrame {
+    /// Create a frame of `unit` ("rows", "range" or "groups") between two bounds. A bound
+    /// is a `WindowFrameBound`, or an offset preceding (for the start) or following
+    /// (for the end) the current row, where None stands for unbounded.
     #[new]
     #[pyo3(signature=(unit, start_bound, end_bound))]
-    pub fn new(unit: &str, start_bound: Option<u64>, end_bound: Option<u64>) -> PyResult<Self> {
+    pub fn new(unit: &str, start_bound: FrameBoundArg, end_bound: FrameBoundArg) -> PyResult<Self> {
         let units = unit.to_ascii_lowercase();
         let units = match units.as_str() {
             "rows" => WindowFrameUnits::Rows,
@@ -182,10 +193,11 @@ impl PyWindowFrame {
             }
         };
         let start_bound = match start_bound {
-            Some(start_bound) => {
+            FrameBoundArg::Bound(bound) => bound.frame_bound,
+            FrameBoundArg::Offset(Some(start_bound)) => {
                 WindowFrameBound::Preceding(ScalarValue::UInt64(Some(start_bound)))
             }
-            None => match units {
+            FrameBoundArg::Offset(None) => match units {
                 WindowFrameUnits::Range => WindowFrameBound::Preceding(ScalarValue::UInt64(None)),
                 WindowFrameUnits::Rows => WindowFrameBound::Preceding(ScalarValue::UInt64(None)),
                 WindowFrameUnits::Groups => {
@@ -197,8 +209,11 @@ impl PyWindowFrame {
             },
         };
         let end_bound = match end_bound {
-            Some(end_bound) => WindowFrameBound::Following(ScalarValue::UInt64(Some(end_bound))),
-            None => match units {
+            FrameBoundArg::Bound(bound) => bound.frame_bound,
+            FrameBoundArg::Offset(Some(end_bound)) => {
+                WindowFrameBound::Following(ScalarValue::UInt64(Some(end_bound)))
+            }
+            FrameBoundArg::Offset(None) => match units {
                 WindowFrameUnits::Rows => WindowFrameBound::Following(ScalarValue::UInt64(None)),
                 WindowFrameUnits::Range => WindowFrameBound::Following(ScalarValue::UInt64(None)),
                 WindowFrameUnits::Groups => {
@@ -235,6 +250,43 @@ impl PyWindowFrame {
 
 #[pymethods]
 impl PyWindowFrameBound {
+    /// The start of the partition, `UNBOUNDED PRECEDING`
+    #[staticmethod]
+    pub fn unbounded_preceding() -> Self {
+        WindowFrameBound::Preceding(ScalarValue::UInt64(None)).into()
+    }
+
+    /// The end of the partition, `UNBOUNDED FOLLOWING`
+    #[staticmethod]
+    pub fn unbounded_following() -> Self {
+        WindowFrameBound::Following(ScalarValue::UInt64(None)).into()
+    }
+
+    /// The current row, `CURRENT ROW`. In a `range` frame this includes the rows that
+    /// sort equal to the current row.
+    #[staticmethod]
+    pub fn current_row() -> Self {
+        WindowFrameBound::CurrentRow.into()
+    }
+
+    /// `offset PRECEDING`, `offset` rows (or values in a `range` frame) before the
+    /// current row
+    #[staticmethod]
+    pub fn preceding(offset: u64) -> Self {
+        WindowFrameBound::Preceding(ScalarValue::UInt64(Some(offset))).into()
+    }
+
+    /// `offset FOLLOWING`, `offset` rows (or values in a `range` frame) after the
+    /// current row
+    #[staticmethod]
+    pub fn following(offset: u64) -> Self {
+        WindowFrameBound::Following(ScalarValue::UInt64(Some(offset))).into()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("WindowFrameBound({})", self.frame_bound)
+    }
+
     /// Returns if the frame bound is current row
     pub fn is_current_row(&self) -> bool {
         matches!(self.frame_bound, WindowFrameBound::CurrentRow)
@@ -0,0 +1,148 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A file format wrapper that skips the marker and temporary files other writers
+//! leave next to their data files
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::Statistics;
+use datafusion::datasource::file_format::arrow::ArrowFormat;
+use datafusion::datasource::file_format::avro::AvroFormat;
+use datafusion::datasource::file_format::csv::CsvFormat;
+use datafusion::datasource::file_format::json::JsonFormat;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::physical_plan::{FileScanConfig, FileSinkConfig};
+use datafusion::error::Result;
+use datafusion::execution::context::SessionState;
+use datafusion::physical_expr::PhysicalSortRequirement;
+use datafusion::physical_plan::{ExecutionPlan, PhysicalExpr};
+use object_store::{ObjectMeta, ObjectStore};
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+
+/// The file format named `format`
+pub(crate) fn parse_file_format(format: &str) -> PyResult<Arc<dyn FileFormat>> {
+    Ok(match format {
+        "parquet" => Arc::new(ParquetFormat::new()),
+        "csv" => Arc::new(CsvFormat::default()),
+        "json" => Arc::new(JsonFormat::default()),
+        "avro" => Arc::new(AvroFormat),
+        "arrow" => Arc::new(ArrowFormat),
+        format => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown file format '{format}', expected one of: parquet, csv, json, avro, arrow"
+            )))
+        }
+    })
+}
+
+/// Files whose name starts with `.` or `_`, such as `_SUCCESS` or `.part-0.crc`, and
+/// empty files are not data files
+fn is_data_file(object: &ObjectMeta) -> bool {
+    let hidden = object
+        .location
+        .filename()
+        .map_or(false, |name| name.starts_with('.') || name.starts_with('_'));
+    !hidden && object.size > 0
+}
+
+/// Wraps a [`FileFormat`] so that listed files that are not data files are skipped
+/// when inferring the schema and when scanning. Writing is left to the wrapped format
+#[derive(Debug)]
+pub(crate) struct DataFilesOnly {
+    inner: Arc<dyn FileFormat>,
+}
+
+impl DataFilesOnly {
+    pub(crate) fn new(inner: Arc<dyn FileFormat>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl FileFormat for DataFilesOnly {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn infer_schema(
+        &self,
+        state: &SessionState,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+    ) -> Result<SchemaRef> {
+        let objects = objects
+            .iter()
+            .filter(|o| is_data_file(o))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.inner.infer_schema(state, store, &objects).await
+    }
+
+    async fn infer_stats(
+        &self,
+        state: &SessionState,
+        store: &Arc<dyn ObjectStore>,
+        table_schema: SchemaRef,
+        object: &ObjectMeta,
+    ) -> Result<Statistics> {
+        if !is_data_file(object) {
+            return Ok(Statistics::new_unknown(&table_schema));
+        }
+        self.inner
+            .infer_stats(state, store, table_schema, object)
+            .await
+    }
+
+    async fn create_physical_plan(
+        &self,
+        state: &SessionState,
+        mut conf: FileScanConfig,
+        filters: Option<&Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        conf.file_groups = conf
+            .file_groups
+            .into_iter()
+            .map(|group| {
+                group
+                    .into_iter()
+                    .filter(|file| is_data_file(&file.object_meta))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|group| !group.is_empty())
+            .collect();
+        self.inner.create_physical_plan(state, conf, filters).await
+    }
+
+    async fn create_writer_physical_plan(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+        state: &SessionState,
+        conf: FileSinkConfig,
+        order_requirements: Option<Vec<PhysicalSortRequirement>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.inner
+            .create_writer_physical_plan(input, state, conf, order_requirements)
+            .await
+    }
+}
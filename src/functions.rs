@@ -15,29 +15,115 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use pyo3::{prelude::*, wrap_pyfunction};
+mod array_filter_udf;
+mod array_has_udf;
+mod array_transform_udf;
+pub(crate) mod fill_null;
+mod flatten_udf;
+pub(crate) mod get_field;
+mod map_udf;
+mod nullif_udf;
+mod nvl2_udf;
+mod ordered_agg;
+mod regexp_replace_udf;
+mod regexp_search_udf;
+pub(crate) mod seeded_random;
+pub(crate) mod session_time;
+mod to_json;
+mod to_timestamp_udf;
+mod zip_with_udf;
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    pybacked::PyBackedStr,
+    types::{PyList, PyTuple},
+    wrap_pyfunction,
+};
 
 use crate::context::PySessionContext;
+use crate::dataframe::PyDataFrame;
 use crate::errors::DataFusionError;
 use crate::expr::conditional_expr::PyCaseBuilder;
-use crate::expr::window::PyWindowFrame;
-use crate::expr::PyExpr;
+use crate::expr::window::{FrameBoundArg, PyWindowFrame};
+use crate::expr::{subquery_from_dataframe, PyExpr};
+use crate::utils::LiteralValue;
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::array::{self as arrow_array, ArrayData};
+use datafusion::arrow::datatypes::{DataType, IntervalUnit, TimeUnit};
+use datafusion::arrow::pyarrow::FromPyArrow;
 use datafusion::execution::FunctionRegistry;
 use datafusion::functions;
 use datafusion::functions_aggregate;
 use datafusion_common::{Column, ScalarValue, TableReference};
-use datafusion_expr::expr::Alias;
+use datafusion_expr::expr::{Alias, Cast};
 use datafusion_expr::{
     aggregate_function,
     expr::{
-        find_df_window_func, AggregateFunction, AggregateFunctionDefinition, Sort, WindowFunction,
+        find_df_window_func, AggregateFunction, AggregateFunctionDefinition, ScalarFunction, Sort,
+        WindowFunction,
     },
-    lit, Expr, WindowFunctionDefinition,
+    lit, BuiltInWindowFunction, Expr, WindowFrame, WindowFunctionDefinition,
 };
 
+/// Returns the sum of the values. Integers are accumulated as Int64 (UInt64 for
+/// unsigned inputs) and a Decimal128(p, s) as Decimal128(min(p + 10, 38), s), so
+/// summing narrow types does not overflow. With `distinct` each value is only added once.
+#[pyfunction]
+#[pyo3(signature = (args, distinct = false))]
+pub fn sum(args: PyExpr, distinct: bool) -> PyExpr {
+    if !distinct {
+        return functions_aggregate::expr_fn::sum(args.expr).into();
+    }
+    Expr::AggregateFunction(AggregateFunction {
+        func_def: AggregateFunctionDefinition::UDF(functions_aggregate::sum::sum_udaf()),
+        args: vec![args.expr],
+        distinct,
+        filter: None,
+        order_by: None,
+        null_treatment: None,
+    })
+    .into()
+}
+
+/// Concatenates the values of a group, separated by `delimiter`, in the order given by
+/// `order_by` if any. NULL values are skipped, and a group without any non-NULL value
+/// gives NULL.
+#[pyfunction]
+#[pyo3(signature = (expr, delimiter, order_by = None))]
+pub fn string_agg(expr: PyExpr, delimiter: &str, order_by: Option<Vec<PyExpr>>) -> PyExpr {
+    Expr::AggregateFunction(AggregateFunction {
+        func_def: AggregateFunctionDefinition::UDF(Arc::new(ordered_agg::string_agg())),
+        args: vec![expr.expr, lit(delimiter)],
+        distinct: false,
+        filter: None,
+        order_by: order_by
+            .map(|x| x.into_iter().map(|x| x.expr).collect::<Vec<_>>())
+            .filter(|x| !x.is_empty()),
+        null_treatment: None,
+    })
+    .into()
+}
+
+/// Aggregates values into a JSON array string, in the order given by `order_by` if
+/// any. Structs become JSON objects and NULL values become `null`.
 #[pyfunction]
-pub fn sum(args: PyExpr) -> PyExpr {
-    functions_aggregate::expr_fn::sum(args.expr).into()
+#[pyo3(signature = (expr, order_by = None))]
+pub fn json_agg(expr: PyExpr, order_by: Option<Vec<PyExpr>>) -> PyExpr {
+    Expr::AggregateFunction(AggregateFunction {
+        func_def: AggregateFunctionDefinition::UDF(Arc::new(ordered_agg::json_agg())),
+        args: vec![expr.expr],
+        distinct: false,
+        filter: None,
+        order_by: order_by
+            .map(|x| x.into_iter().map(|x| x.expr).collect::<Vec<_>>())
+            .filter(|x| !x.is_empty()),
+        null_treatment: None,
+    })
+    .into()
 }
 
 #[pyfunction]
@@ -116,6 +202,78 @@ pub fn last_value(
     .into()
 }
 
+/// Aggregates values into an array, in the order given by `order_by` if any. Combined
+/// with `distinct`, `order_by` may only sort by the aggregated expression: the unique
+/// non-NULL values are returned in that order.
+#[pyfunction]
+#[pyo3(signature = (*args, distinct = false, filter = None, order_by = None))]
+pub fn array_agg(
+    args: Vec<PyExpr>,
+    distinct: bool,
+    filter: Option<PyExpr>,
+    order_by: Option<Vec<PyExpr>>,
+) -> PyResult<PyExpr> {
+    let args = args.into_iter().map(|x| x.expr).collect::<Vec<_>>();
+    let filter = filter.map(|x| x.expr);
+    let order_by = order_by
+        .map(|x| x.into_iter().map(|x| x.expr).collect::<Vec<_>>())
+        .filter(|x| !x.is_empty());
+
+    let (func_def, filter) = match (distinct, &order_by) {
+        (true, Some(order_by)) => {
+            // The builtin cannot evaluate an order sensitive DISTINCT, so the values
+            // are deduplicated after sorting them on the aggregated expression.
+            let [arg] = args.as_slice() else {
+                return Err(DataFusionError::Common(
+                    "array_agg with distinct and order_by takes a single expression".to_string(),
+                )
+                .into());
+            };
+            let sorts_by_arg = match order_by.as_slice() {
+                [Expr::Sort(Sort { expr, .. })] => expr.as_ref() == arg,
+                _ => false,
+            };
+            if !sorts_by_arg {
+                return Err(DataFusionError::Common(
+                    "In array_agg with distinct, order_by may only sort by the aggregated expression"
+                        .to_string(),
+                )
+                .into());
+            }
+            let is_not_null = arg.clone().is_not_null();
+            let filter = match filter {
+                Some(filter) => filter.and(is_not_null),
+                None => is_not_null,
+            };
+            (
+                AggregateFunctionDefinition::UDF(Arc::new(ordered_agg::array_agg_distinct())),
+                Some(filter),
+            )
+        }
+        _ => (
+            AggregateFunctionDefinition::BuiltIn(aggregate_function::AggregateFunction::ArrayAgg),
+            filter,
+        ),
+    };
+
+    Ok(Expr::AggregateFunction(AggregateFunction {
+        func_def,
+        args,
+        distinct,
+        filter: filter.map(Box::new),
+        order_by,
+        null_treatment: None,
+    })
+    .into())
+}
+
+/// Creates a scalar subquery expression, the subquery must return a single column and
+/// at most one row
+#[pyfunction]
+fn scalar_subquery(subquery: PyDataFrame) -> PyResult<PyExpr> {
+    Ok(Expr::ScalarSubquery(subquery_from_dataframe(&subquery)?).into())
+}
+
 #[pyfunction]
 fn in_list(expr: PyExpr, value: Vec<PyExpr>, negated: bool) -> PyExpr {
     datafusion_expr::in_list(
@@ -140,6 +298,351 @@ fn array(exprs: Vec<PyExpr>) -> PyExpr {
     make_array(exprs)
 }
 
+/// Returns true if `first_array` contains `second_array`. By default this is the
+/// DataFusion builtin, where searching for a NULL element is unknown. With
+/// `null_matches` it checks whether the array contains a NULL instead.
+#[pyfunction]
+#[pyo3(signature = (first_array, second_array, null_matches = false))]
+fn array_has(first_array: PyExpr, second_array: PyExpr, null_matches: bool) -> PyExpr {
+    if null_matches {
+        array_has_udf::array_has_null_matching()
+            .call(vec![first_array.expr, second_array.expr])
+            .into()
+    } else {
+        datafusion_functions_array::expr_fn::array_has(first_array.expr, second_array.expr).into()
+    }
+}
+
+/// Returns true if `first_array` contains every element of `second_array`. NULL
+/// elements are handled like in `array_has`.
+#[pyfunction]
+#[pyo3(signature = (first_array, second_array, null_matches = false))]
+fn array_has_all(first_array: PyExpr, second_array: PyExpr, null_matches: bool) -> PyExpr {
+    if null_matches {
+        array_has_udf::array_has_all_null_matching()
+            .call(vec![first_array.expr, second_array.expr])
+            .into()
+    } else {
+        datafusion_functions_array::expr_fn::array_has_all(first_array.expr, second_array.expr)
+            .into()
+    }
+}
+
+/// Returns true if `first_array` contains any element of `second_array`. NULL
+/// elements are handled like in `array_has`.
+#[pyfunction]
+#[pyo3(signature = (first_array, second_array, null_matches = false))]
+fn array_has_any(first_array: PyExpr, second_array: PyExpr, null_matches: bool) -> PyExpr {
+    if null_matches {
+        array_has_udf::array_has_any_null_matching()
+            .call(vec![first_array.expr, second_array.expr])
+            .into()
+    } else {
+        datafusion_functions_array::expr_fn::array_has_any(first_array.expr, second_array.expr)
+            .into()
+    }
+}
+
+/// Converts the argument to a timestamp. Integers are seconds since the epoch unless
+/// `unit` ("seconds", "millis", "micros" or "nanos") says otherwise, in which case the
+/// result keeps that precision. Strings are parsed the same way whatever the unit.
+#[pyfunction]
+#[pyo3(signature = (*args, unit = None))]
+fn to_timestamp(args: Vec<PyExpr>, unit: Option<&str>) -> PyResult<PyExpr> {
+    let args = args.into_iter().map(|e| e.into()).collect::<Vec<_>>();
+    let unit = match unit {
+        None => return Ok(functions::expr_fn::to_timestamp(args).into()),
+        Some("seconds") => TimeUnit::Second,
+        Some("millis") => TimeUnit::Millisecond,
+        Some("micros") => TimeUnit::Microsecond,
+        Some("nanos") => TimeUnit::Nanosecond,
+        Some(other) => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown unit '{other}', expected seconds, millis, micros or nanos"
+            )))
+        }
+    };
+    Ok(to_timestamp_udf::to_timestamp_with_unit(unit)
+        .call(args)
+        .into())
+}
+
+/// Converts the argument to a nanosecond timestamp in the time zone `tz`, an IANA
+/// name such as "Europe/Paris" or a fixed offset such as "+02:00". Timestamps
+/// without a time zone and strings without an offset are read as local times in
+/// `tz`; timestamps that already have a time zone keep the same instant.
+#[pyfunction]
+fn to_timestamp_tz(expr: PyExpr, tz: &str) -> PyResult<PyExpr> {
+    Tz::from_str(tz).map_err(|_| PyValueError::new_err(format!("Unknown time zone '{tz}'")))?;
+    let data_type = DataType::Timestamp(TimeUnit::Nanosecond, Some(tz.into()));
+    Ok(Expr::Cast(Cast::new(Box::new(expr.expr), data_type)).into())
+}
+
+/// Returns the characters of `string` from the 1-based `position` on, following
+/// PostgreSQL. With a `length` only the characters from `position` to
+/// `position + length - 1` are kept, so a start before the first character eats
+/// into the length: `substr('alphabet', -2, 5)` is `'al'`. Without a `length`, or
+/// when it is `None`, the rest of the string is returned. A negative length is an
+/// error and a NULL argument value gives NULL.
+#[pyfunction]
+#[pyo3(signature = (string, position, length = None))]
+fn substr(string: PyExpr, position: PyExpr, length: Option<PyExpr>) -> PyExpr {
+    match length {
+        Some(length) => functions::expr_fn::substring(string.expr, position.expr, length.expr),
+        None => functions::expr_fn::substr(string.expr, position.expr),
+    }
+    .into()
+}
+
+/// Calls the function `name` registered on `ctx`, which follows the session time
+/// zone, or the builtin `fallback`, which works in UTC, when there is no `ctx`
+fn session_time_fn(
+    name: &str,
+    ctx: Option<PySessionContext>,
+    fallback: fn() -> Expr,
+) -> PyResult<PyExpr> {
+    match ctx {
+        Some(ctx) => Ok(ctx.ctx.udf(name)?.call(vec![]).into()),
+        None => Ok(fallback().into()),
+    }
+}
+
+/// Returns the current timestamp. With `ctx` the result is in the time zone of the
+/// session (`datafusion.execution.time_zone`), otherwise in UTC.
+#[pyfunction]
+#[pyo3(signature = (ctx = None))]
+fn now(ctx: Option<PySessionContext>) -> PyResult<PyExpr> {
+    session_time_fn("now", ctx, functions::expr_fn::now)
+}
+
+/// Returns the current date. With `ctx` it is the calendar day in the time zone of
+/// the session (`datafusion.execution.time_zone`), otherwise in UTC.
+#[pyfunction]
+#[pyo3(signature = (ctx = None))]
+fn current_date(ctx: Option<PySessionContext>) -> PyResult<PyExpr> {
+    session_time_fn("current_date", ctx, functions::expr_fn::current_date)
+}
+
+/// Returns the current time of day. With `ctx` it is the local time in the time
+/// zone of the session (`datafusion.execution.time_zone`), otherwise in UTC.
+#[pyfunction]
+#[pyo3(signature = (ctx = None))]
+fn current_time(ctx: Option<PySessionContext>) -> PyResult<PyExpr> {
+    session_time_fn("current_time", ctx, functions::expr_fn::current_time)
+}
+
+/// Builds a literal of the pyarrow type `data_type` from the Python `value`
+fn typed_literal(
+    py: Python,
+    value: &Bound<'_, PyAny>,
+    data_type: Bound<'_, PyAny>,
+) -> PyResult<PyExpr> {
+    let values = PyList::new_bound(py, [value]);
+    let array = py
+        .import_bound("pyarrow")?
+        .call_method1("array", (values, data_type))?;
+    let array = arrow_array::make_array(ArrayData::from_pyarrow_bound(&array)?);
+    Ok(lit(ScalarValue::try_from_array(&array, 0)?).into())
+}
+
+/// Creates a Decimal128(`precision`, `scale`) literal from an int, float, string or
+/// `decimal.Decimal`. Raises a ValueError if the value does not fit exactly.
+#[pyfunction]
+fn lit_decimal(py: Python, value: &Bound<'_, PyAny>, precision: u8, scale: i8) -> PyResult<PyExpr> {
+    // going through the string form keeps floats from adding binary noise digits
+    let decimal = py
+        .import_bound("decimal")?
+        .getattr("Decimal")?
+        .call1((value.str()?,))?;
+    let data_type = py
+        .import_bound("pyarrow")?
+        .call_method1("decimal128", (precision, scale))?;
+    if !decimal_fits(&decimal, precision, scale)? {
+        return Err(PyValueError::new_err(format!(
+            "Value {value} does not fit in Decimal128({precision}, {scale})"
+        )));
+    }
+    typed_literal(py, &decimal, data_type)
+}
+
+/// Whether the `decimal.Decimal` can be stored exactly with `precision` digits, of
+/// which `scale` are fractional. NaN and infinities are left to pyarrow to reject.
+fn decimal_fits(decimal: &Bound<'_, PyAny>, precision: u8, scale: i8) -> PyResult<bool> {
+    let (_sign, digits, exponent): (u8, Vec<u8>, Bound<'_, PyAny>) =
+        decimal.call_method0("as_tuple")?.extract()?;
+    let Ok(exponent) = exponent.extract::<i64>() else {
+        return Ok(true);
+    };
+    let digits = match digits.iter().position(|&d| d != 0) {
+        Some(first) => &digits[first..],
+        None => return Ok(true),
+    };
+    // the number of digits gained (or lost when negative) by rescaling to `scale`
+    let shift = exponent + scale as i64;
+    let len = digits.len() as i64;
+    if shift < 0 {
+        let dropped = (-shift).min(len) as usize;
+        if digits[digits.len() - dropped..].iter().any(|&d| d != 0) {
+            return Ok(false);
+        }
+    }
+    Ok(len + shift <= precision as i64)
+}
+
+/// Creates a timestamp literal with the given unit ("s", "ms", "us" or "ns") and
+/// optional time zone from a `datetime.datetime` or an integer since the epoch.
+#[pyfunction]
+#[pyo3(signature = (value, unit = "us", tz = None))]
+fn lit_timestamp(
+    py: Python,
+    value: &Bound<'_, PyAny>,
+    unit: &str,
+    tz: Option<&str>,
+) -> PyResult<PyExpr> {
+    let data_type = py
+        .import_bound("pyarrow")?
+        .call_method1("timestamp", (unit, tz))?;
+    typed_literal(py, value, data_type)
+}
+
+/// Creates a month-day-nanosecond interval literal.
+#[pyfunction]
+#[pyo3(signature = (months = 0, days = 0, nanoseconds = 0))]
+fn lit_interval(py: Python, months: i32, days: i32, nanoseconds: i64) -> PyResult<PyExpr> {
+    let data_type = py
+        .import_bound("pyarrow")?
+        .call_method0("month_day_nano_interval")?;
+    let value: PyObject = (months, days, nanoseconds).into_py(py);
+    typed_literal(py, value.bind(py), data_type)
+}
+
+/// Creates a month-day-nanosecond interval literal from a string such as
+/// `"1 month 2 days"` or `"90 minutes"`, with the syntax of SQL `INTERVAL` strings.
+#[pyfunction]
+fn interval(value: &str) -> PyResult<PyExpr> {
+    let value = ScalarValue::try_from_string(
+        value.to_string(),
+        &DataType::Interval(IntervalUnit::MonthDayNano),
+    )
+    .map_err(|e| PyValueError::new_err(format!("Invalid interval '{value}': {e}")))?;
+    Ok(lit(value).into())
+}
+
+/// Adds `interval` to a date or timestamp. Months are added first, clamping the day to
+/// the end of a shorter month (January 31 plus one month is the last day of February),
+/// then days and the remaining time.
+#[pyfunction]
+fn date_add(date: PyExpr, interval: PyExpr) -> PyExpr {
+    (date.expr + interval.expr).into()
+}
+
+/// Subtracts `interval` from a date or timestamp, clamping the day to the end of a
+/// shorter month like `date_add`.
+#[pyfunction]
+fn date_sub(date: PyExpr, interval: PyExpr) -> PyExpr {
+    (date.expr - interval.expr).into()
+}
+
+/// Returns `if_not_null` where `test` is not NULL and `if_null` where it is. This is
+/// the DataFusion builtin, which coerces the three arguments to a common type.
+#[pyfunction]
+fn nvl2(test: PyExpr, if_not_null: PyExpr, if_null: PyExpr) -> PyExpr {
+    nvl2_udf::nvl2()
+        .call(vec![test.expr, if_not_null.expr, if_null.expr])
+        .into()
+}
+
+/// Replaces the NaN values of a float column with NULL.
+#[pyfunction]
+fn nullif_nan(expr: PyExpr) -> PyExpr {
+    nullif_udf::nullif_nan().call(vec![expr.expr]).into()
+}
+
+/// Replaces the positive and negative infinite values of a float column with NULL.
+#[pyfunction]
+fn nullif_inf(expr: PyExpr) -> PyExpr {
+    nullif_udf::nullif_inf().call(vec![expr.expr]).into()
+}
+
+/// Returns a map pairing each element of the `keys` list with the element of the
+/// `values` list at the same position. Null and duplicate keys raise an error.
+#[pyfunction]
+fn map_from_arrays(keys: PyExpr, values: PyExpr) -> PyExpr {
+    map_udf::map_from_arrays_udf()
+        .call(vec![keys.expr, values.expr])
+        .into()
+}
+
+/// Returns a map pairing each element of the `keys` list with the element of the
+/// `values` list at the same position. Null and duplicate keys raise an error.
+#[pyfunction]
+fn map(keys: PyExpr, values: PyExpr) -> PyExpr {
+    map_udf::map_udf().call(vec![keys.expr, values.expr]).into()
+}
+
+/// Returns the value of `key` in the map, or NULL when the map has no such key.
+#[pyfunction]
+fn map_extract(map: PyExpr, key: PyExpr) -> PyExpr {
+    map_udf::map_extract_udf()
+        .call(vec![map.expr, key.expr])
+        .into()
+}
+
+/// Returns a map built from a list of `(key, value)` structs. Null and duplicate
+/// keys raise an error.
+#[pyfunction]
+fn map_from_entries(entries: PyExpr) -> PyExpr {
+    map_udf::map_from_entries_udf()
+        .call(vec![entries.expr])
+        .into()
+}
+
+/// Applies the scalar function registered on `ctx` as `func` to the elements of the
+/// two lists pairwise. The shorter list is padded with NULLs.
+#[pyfunction]
+fn zip_with(
+    first_array: PyExpr,
+    second_array: PyExpr,
+    func: &str,
+    ctx: PySessionContext,
+) -> PyResult<PyExpr> {
+    let func = ctx.ctx.udf(func)?;
+    Ok(zip_with_udf::zip_with(func)
+        .call(vec![first_array.expr, second_array.expr])
+        .into())
+}
+
+/// Keeps the elements of `array` for which the boolean scalar function registered on
+/// `ctx` as `func` returns true, in their original order. Elements for which it
+/// returns NULL are dropped.
+#[pyfunction]
+fn array_filter(array: PyExpr, func: &str, ctx: PySessionContext) -> PyResult<PyExpr> {
+    let func = ctx.ctx.udf(func)?;
+    Ok(array_filter_udf::array_filter(func)
+        .call(vec![array.expr])
+        .into())
+}
+
+/// Applies the scalar function registered on `ctx` as `func` to each element of
+/// `array`, NULL elements included. The element type of the result is the return type
+/// of the function.
+#[pyfunction]
+fn array_transform(array: PyExpr, func: &str, ctx: PySessionContext) -> PyResult<PyExpr> {
+    let func = ctx.ctx.udf(func)?;
+    Ok(array_transform_udf::array_transform(func)
+        .call(vec![array.expr])
+        .into())
+}
+
+/// Flattens lists nested to any depth into a single list of their innermost
+/// elements. NULL lists below the top level contribute no elements.
+#[pyfunction]
+fn array_flatten_deep(array: PyExpr) -> PyExpr {
+    flatten_udf::array_flatten_deep()
+        .call(vec![array.expr])
+        .into()
+}
+
 #[pyfunction]
 #[pyo3(signature = (*exprs))]
 fn array_concat(exprs: Vec<PyExpr>) -> PyExpr {
@@ -238,21 +741,109 @@ fn regexp_match(values: PyExpr, regex: PyExpr, flags: Option<PyExpr>) -> PyResul
 }
 
 #[pyfunction]
+#[pyo3(signature = (string, pattern, replacement, flags = None, position = None, occurrence = None))]
 /// Replaces substring(s) matching a POSIX regular expression.
+///
+/// `position` (1-based) and `occurrence` follow Oracle semantics: only the text
+/// from `position` onward is searched, and `occurrence` 0 replaces every match
+/// while `n` replaces only the nth one. Without an `occurrence` the `g` flag
+/// decides between the first and all matches.
 fn regexp_replace(
     string: PyExpr,
     pattern: PyExpr,
     replacement: PyExpr,
     flags: Option<PyExpr>,
+    position: Option<PyExpr>,
+    occurrence: Option<PyExpr>,
 ) -> PyResult<PyExpr> {
-    Ok(functions::expr_fn::regexp_replace(
+    if position.is_none() && occurrence.is_none() {
+        return Ok(functions::expr_fn::regexp_replace(
+            string.into(),
+            pattern.into(),
+            replacement.into(),
+            flags.map(|x| x.expr),
+        )
+        .into());
+    }
+
+    let args = vec![
         string.into(),
         pattern.into(),
         replacement.into(),
-        flags.map(|x| x.expr),
-    )
-    .into())
+        flags.map(|x| x.expr).unwrap_or_else(|| lit("")),
+        position.map(|x| x.expr).unwrap_or_else(|| lit(1_i64)),
+        occurrence
+            .map(|x| x.expr)
+            .unwrap_or_else(|| lit(ScalarValue::Int64(None))),
+    ];
+    Ok(regexp_replace_udf::regexp_replace_nth().call(args).into())
+}
+
+/// Returns the number of non-overlapping matches of `pattern` in `string`, searching
+/// from the 1-based character `start`.
+#[pyfunction]
+#[pyo3(signature = (string, pattern, start = None, flags = None))]
+fn regexp_count(
+    string: PyExpr,
+    pattern: PyExpr,
+    start: Option<PyExpr>,
+    flags: Option<PyExpr>,
+) -> PyExpr {
+    let args = vec![
+        string.into(),
+        pattern.into(),
+        start.map(|x| x.expr).unwrap_or_else(|| lit(1_i64)),
+        lit(1_i64),
+        flags.map(|x| x.expr).unwrap_or_else(|| lit("")),
+    ];
+    regexp_search_udf::regexp_count().call(args).into()
+}
+
+/// Returns the 1-based character position of the `occurrence`th non-overlapping
+/// match of `pattern` in `string`, searching from the 1-based character `start`, or
+/// 0 when there is no such match.
+#[pyfunction]
+#[pyo3(signature = (string, pattern, start = None, occurrence = None, flags = None))]
+fn regexp_instr(
+    string: PyExpr,
+    pattern: PyExpr,
+    start: Option<PyExpr>,
+    occurrence: Option<PyExpr>,
+    flags: Option<PyExpr>,
+) -> PyExpr {
+    let args = vec![
+        string.into(),
+        pattern.into(),
+        start.map(|x| x.expr).unwrap_or_else(|| lit(1_i64)),
+        occurrence.map(|x| x.expr).unwrap_or_else(|| lit(1_i64)),
+        flags.map(|x| x.expr).unwrap_or_else(|| lit("")),
+    ];
+    regexp_search_udf::regexp_instr().call(args).into()
+}
+
+/// Returns the first of its arguments that is not null.
+/// Nested `coalesce` arguments are flattened into a single call.
+#[pyfunction]
+#[pyo3(signature = (*args))]
+fn coalesce(args: Vec<PyExpr>) -> PyExpr {
+    let args = args
+        .into_iter()
+        .flat_map(|e| flatten_coalesce(e.expr))
+        .collect::<Vec<_>>();
+    functions::expr_fn::coalesce(args).into()
+}
+
+/// `coalesce(coalesce(a, b), c)` is equivalent to `coalesce(a, b, c)`, so the
+/// arguments of any directly nested `coalesce` are spliced into the parent.
+fn flatten_coalesce(expr: Expr) -> Vec<Expr> {
+    match expr {
+        Expr::ScalarFunction(ScalarFunction { func, args }) if func.name() == "coalesce" => {
+            args.into_iter().flat_map(flatten_coalesce).collect()
+        }
+        expr => vec![expr],
+    }
 }
+
 /// Creates a new Sort Expr
 #[pyfunction]
 fn order_by(expr: PyExpr, asc: Option<bool>, nulls_first: Option<bool>) -> PyResult<PyExpr> {
@@ -285,6 +876,26 @@ fn col(name: &str) -> PyResult<PyExpr> {
     })
 }
 
+/// Create column reference Exprs for several names, given as separate arguments and/or
+/// iterables of names. A name like `t.a` refers to the column `a` of the relation `t`,
+/// a name containing a dot is written in double quotes, e.g. `'"a.b"'`.
+#[pyfunction]
+#[pyo3(signature = (*names))]
+fn cols(names: &Bound<'_, PyTuple>) -> PyResult<Vec<PyExpr>> {
+    let mut exprs = vec![];
+    for arg in names.iter() {
+        if let Ok(name) = arg.extract::<PyBackedStr>() {
+            exprs.push(datafusion_expr::col(&*name).into());
+            continue;
+        }
+        for name in arg.iter()? {
+            let name = name?.extract::<PyBackedStr>()?;
+            exprs.push(datafusion_expr::col(&*name).into());
+        }
+    }
+    Ok(exprs)
+}
+
 /// Create a COUNT(1) aggregate expression
 #[pyfunction]
 fn count_star() -> PyResult<PyExpr> {
@@ -302,6 +913,22 @@ fn count_star() -> PyResult<PyExpr> {
     })
 }
 
+/// Grouping sets for every prefix of `exprs`, from all of them down to the grand
+/// total, for use as a grouping expression of `aggregate`
+#[pyfunction]
+#[pyo3(signature = (*exprs))]
+fn rollup(exprs: Vec<PyExpr>) -> PyExpr {
+    datafusion_expr::expr_fn::rollup(exprs.into_iter().map(|e| e.expr).collect()).into()
+}
+
+/// Grouping sets for every subset of `exprs`, for use as a grouping expression of
+/// `aggregate`
+#[pyfunction]
+#[pyo3(signature = (*exprs))]
+fn cube(exprs: Vec<PyExpr>) -> PyExpr {
+    datafusion_expr::expr_fn::cube(exprs.into_iter().map(|e| e.expr).collect()).into()
+}
+
 /// Create a CASE WHEN statement with literal WHEN expressions for comparison to the base expression.
 #[pyfunction]
 fn case(expr: PyExpr) -> PyResult<PyCaseBuilder> {
@@ -325,6 +952,7 @@ fn window(
             ctx.ctx
                 .udaf(name)
                 .map(WindowFunctionDefinition::AggregateUDF)
+                .or_else(|_| ctx.ctx.udwf(name).map(WindowFunctionDefinition::WindowUDF))
                 .ok()
         })
     });
@@ -332,27 +960,182 @@ fn window(
         return Err(DataFusionError::Common("window function not found".to_string()).into());
     }
     let fun = fun.unwrap();
-    let window_frame = window_frame
-        .unwrap_or_else(|| PyWindowFrame::new("rows", None, Some(0)).unwrap())
-        .into();
-    Ok(PyExpr {
-        expr: datafusion_expr::Expr::WindowFunction(WindowFunction {
-            fun,
-            args: args.into_iter().map(|x| x.expr).collect::<Vec<_>>(),
-            partition_by: partition_by
-                .unwrap_or_default()
-                .into_iter()
-                .map(|x| x.expr)
-                .collect::<Vec<_>>(),
-            order_by: order_by
-                .unwrap_or_default()
-                .into_iter()
-                .map(|x| x.expr)
-                .collect::<Vec<_>>(),
-            window_frame,
-            null_treatment: None,
-        }),
+    let window_frame = window_frame.unwrap_or_else(|| {
+        PyWindowFrame::new(
+            "rows",
+            FrameBoundArg::Offset(None),
+            FrameBoundArg::Offset(Some(0)),
+        )
+        .unwrap()
+    });
+    Ok(window_expr(
+        fun,
+        args,
+        partition_by,
+        order_by,
+        Some(window_frame),
+    ))
+}
+
+/// Without a `window_frame` the SQL default is used: the rows up to the last peer of
+/// the current row when there is an `order_by`, otherwise the whole partition. The
+/// built-in wrappers below rely on it, ranking functions ignore the frame.
+fn window_expr(
+    fun: WindowFunctionDefinition,
+    args: Vec<PyExpr>,
+    partition_by: Option<Vec<PyExpr>>,
+    order_by: Option<Vec<PyExpr>>,
+    window_frame: Option<PyWindowFrame>,
+) -> PyExpr {
+    let order_by = order_by
+        .unwrap_or_default()
+        .into_iter()
+        .map(|x| x.expr)
+        .collect::<Vec<_>>();
+    let window_frame = match window_frame {
+        Some(window_frame) => window_frame.into(),
+        None => WindowFrame::new((!order_by.is_empty()).then_some(false)),
+    };
+    datafusion_expr::Expr::WindowFunction(WindowFunction {
+        fun,
+        args: args.into_iter().map(|x| x.expr).collect::<Vec<_>>(),
+        partition_by: partition_by
+            .unwrap_or_default()
+            .into_iter()
+            .map(|x| x.expr)
+            .collect::<Vec<_>>(),
+        order_by,
+        window_frame,
+        null_treatment: None,
     })
+    .into()
+}
+
+macro_rules! ranking_function {
+    ($NAME: ident, $FUNC: ident, $DOC: expr) => {
+        #[doc = $DOC]
+        #[pyfunction]
+        #[pyo3(signature = (partition_by=None, order_by=None))]
+        fn $NAME(partition_by: Option<Vec<PyExpr>>, order_by: Option<Vec<PyExpr>>) -> PyExpr {
+            window_expr(
+                WindowFunctionDefinition::BuiltInWindowFunction(BuiltInWindowFunction::$FUNC),
+                vec![],
+                partition_by,
+                order_by,
+                None,
+            )
+        }
+    };
+}
+
+ranking_function!(
+    row_number,
+    RowNumber,
+    "The number of the current row within its partition, counting from 1"
+);
+ranking_function!(
+    rank,
+    Rank,
+    "The rank of the current row with gaps, peers of the `order_by` share a rank"
+);
+ranking_function!(
+    dense_rank,
+    DenseRank,
+    "The rank of the current row without gaps, peers of the `order_by` share a rank"
+);
+ranking_function!(
+    percent_rank,
+    PercentRank,
+    "The relative rank of the current row, (rank - 1) / (partition rows - 1)"
+);
+ranking_function!(
+    cume_dist,
+    CumeDist,
+    "The fraction of partition rows up to and including the peers of the current row"
+);
+
+/// The value of `expr` at the row `offset` rows before the current row in its
+/// partition, or `default` (NULL when not given) when there is no such row. The
+/// default is cast to the type of `expr`, and an offset of 0 is the current row.
+#[pyfunction]
+#[pyo3(signature = (expr, offset=1, default=None, partition_by=None, order_by=None))]
+fn lag(
+    expr: PyExpr,
+    offset: i64,
+    default: Option<LiteralValue>,
+    partition_by: Option<Vec<PyExpr>>,
+    order_by: Option<Vec<PyExpr>>,
+) -> PyExpr {
+    shift_window(
+        BuiltInWindowFunction::Lag,
+        expr,
+        offset,
+        default,
+        partition_by,
+        order_by,
+    )
+}
+
+/// The value of `expr` at the row `offset` rows after the current row in its
+/// partition, or `default` (NULL when not given) when there is no such row. The
+/// default is cast to the type of `expr`, and an offset of 0 is the current row.
+#[pyfunction]
+#[pyo3(signature = (expr, offset=1, default=None, partition_by=None, order_by=None))]
+fn lead(
+    expr: PyExpr,
+    offset: i64,
+    default: Option<LiteralValue>,
+    partition_by: Option<Vec<PyExpr>>,
+    order_by: Option<Vec<PyExpr>>,
+) -> PyExpr {
+    shift_window(
+        BuiltInWindowFunction::Lead,
+        expr,
+        offset,
+        default,
+        partition_by,
+        order_by,
+    )
+}
+
+fn shift_window(
+    fun: BuiltInWindowFunction,
+    expr: PyExpr,
+    offset: i64,
+    default: Option<LiteralValue>,
+    partition_by: Option<Vec<PyExpr>>,
+    order_by: Option<Vec<PyExpr>>,
+) -> PyExpr {
+    let mut args = vec![expr, lit(offset).into()];
+    args.extend(default.map(|value| lit(value.0).into()));
+    window_expr(
+        WindowFunctionDefinition::BuiltInWindowFunction(fun),
+        args,
+        partition_by,
+        order_by,
+        None,
+    )
+}
+
+/// Divides the rows of each partition into `n` buckets as evenly as possible and
+/// returns the bucket number of the current row, counting from 1
+#[pyfunction]
+#[pyo3(signature = (n, partition_by=None, order_by=None))]
+fn ntile(
+    n: u64,
+    partition_by: Option<Vec<PyExpr>>,
+    order_by: Option<Vec<PyExpr>>,
+) -> PyResult<PyExpr> {
+    if n == 0 {
+        return Err(PyValueError::new_err("ntile requires at least one bucket"));
+    }
+    Ok(window_expr(
+        WindowFunctionDefinition::BuiltInWindowFunction(BuiltInWindowFunction::Ntile),
+        vec![lit(n as i64).into()],
+        partition_by,
+        order_by,
+        None,
+    ))
 }
 
 macro_rules! aggregate_function {
@@ -478,7 +1261,6 @@ expr_fn!(
 expr_fn!(length, string);
 expr_fn!(char_length, string);
 expr_fn!(chr, arg, "Returns the character with the given code.");
-expr_fn_vec!(coalesce);
 expr_fn!(cos, num);
 expr_fn!(cosh, num);
 expr_fn!(degrees, num);
@@ -547,7 +1329,6 @@ expr_fn!(
 expr_fn!(sqrt, num);
 expr_fn!(starts_with, string prefix, "Returns true if string starts with prefix.");
 expr_fn!(strpos, string substring, "Returns starting index of specified substring within string, or zero if it's not present. (Same as position(substring in string), but note the reversed argument order.)");
-expr_fn!(substr, string position);
 expr_fn!(substring, string position length);
 expr_fn!(tan, num);
 expr_fn!(tanh, num);
@@ -556,13 +1337,10 @@ expr_fn!(
     arg1,
     "Converts the number to its equivalent hexadecimal representation."
 );
-expr_fn!(now);
-expr_fn_vec!(to_timestamp);
 expr_fn_vec!(to_timestamp_millis);
 expr_fn_vec!(to_timestamp_micros);
 expr_fn_vec!(to_timestamp_seconds);
-expr_fn!(current_date);
-expr_fn!(current_time);
+expr_fn_vec!(to_timestamp_nanos);
 expr_fn!(date_part, part date);
 expr_fn!(datepart, date_part, part date);
 expr_fn!(date_trunc, part date);
@@ -577,6 +1355,10 @@ expr_fn!(uuid);
 expr_fn_vec!(r#struct); // Use raw identifier since struct is a keyword
 expr_fn_vec!(named_struct);
 expr_fn!(from_unixtime, unixtime);
+expr_fn_vec!(
+    to_unixtime,
+    "Converts a timestamp, date or string to whole seconds since the Unix epoch as Int64, truncating any fraction of a second. Strings are parsed with the optional formats that follow them."
+);
 expr_fn!(arrow_typeof, arg_1);
 expr_fn!(random);
 
@@ -599,9 +1381,6 @@ array_fn!(list_element, array_element, array element);
 array_fn!(list_extract, array_element, array element);
 array_fn!(array_length, array);
 array_fn!(list_length, array_length, array);
-array_fn!(array_has, first_array second_array);
-array_fn!(array_has_all, first_array second_array);
-array_fn!(array_has_any, first_array second_array);
 array_fn!(array_positions, array_positions, array element);
 array_fn!(list_positions, array_positions, array element);
 array_fn!(array_ndims, array);
@@ -634,6 +1413,7 @@ array_fn!(list_except, array_except, first_array second_array);
 array_fn!(array_resize, array size value);
 array_fn!(list_resize, array_resize, array size value);
 array_fn!(flatten, array);
+array_fn!(array_flatten, flatten, array);
 array_fn!(range, start stop step);
 
 aggregate_function!(approx_distinct, ApproxDistinct);
@@ -643,7 +1423,6 @@ aggregate_function!(
     approx_percentile_cont_with_weight,
     ApproxPercentileContWithWeight
 );
-aggregate_function!(array_agg, ArrayAgg);
 aggregate_function!(avg, Avg);
 aggregate_function!(corr, Correlation);
 aggregate_function!(count, Count);
@@ -675,6 +1454,8 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(acos))?;
     m.add_wrapped(wrap_pyfunction!(acosh))?;
     m.add_wrapped(wrap_pyfunction!(approx_distinct))?;
+    m.add_wrapped(wrap_pyfunction!(json_agg))?;
+    m.add_wrapped(wrap_pyfunction!(string_agg))?;
     m.add_wrapped(wrap_pyfunction!(alias))?;
     m.add_wrapped(wrap_pyfunction!(approx_median))?;
     m.add_wrapped(wrap_pyfunction!(approx_percentile_cont))?;
@@ -700,6 +1481,7 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(coalesce))?;
     m.add_wrapped(wrap_pyfunction!(case))?;
     m.add_wrapped(wrap_pyfunction!(col))?;
+    m.add_wrapped(wrap_pyfunction!(cols))?;
     m.add_wrapped(wrap_pyfunction!(concat_ws))?;
     m.add_wrapped(wrap_pyfunction!(concat))?;
     m.add_wrapped(wrap_pyfunction!(corr))?;
@@ -724,11 +1506,16 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(factorial))?;
     m.add_wrapped(wrap_pyfunction!(floor))?;
     m.add_wrapped(wrap_pyfunction!(from_unixtime))?;
+    m.add_wrapped(wrap_pyfunction!(to_unixtime))?;
     m.add_wrapped(wrap_pyfunction!(gcd))?;
     m.add_wrapped(wrap_pyfunction!(grouping))?;
+    m.add_wrapped(wrap_pyfunction!(rollup))?;
+    m.add_wrapped(wrap_pyfunction!(cube))?;
     m.add_wrapped(wrap_pyfunction!(in_list))?;
     m.add_wrapped(wrap_pyfunction!(initcap))?;
     m.add_wrapped(wrap_pyfunction!(isnan))?;
+    m.add_wrapped(wrap_pyfunction!(nullif_nan))?;
+    m.add_wrapped(wrap_pyfunction!(nullif_inf))?;
     m.add_wrapped(wrap_pyfunction!(iszero))?;
     m.add_wrapped(wrap_pyfunction!(lcm))?;
     m.add_wrapped(wrap_pyfunction!(left))?;
@@ -742,6 +1529,13 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(ltrim))?;
     m.add_wrapped(wrap_pyfunction!(max))?;
     m.add_wrapped(wrap_pyfunction!(make_array))?;
+    m.add_wrapped(wrap_pyfunction!(map))?;
+    m.add_wrapped(wrap_pyfunction!(map_extract))?;
+    m.add_wrapped(wrap_pyfunction!(map_from_arrays))?;
+    m.add_wrapped(wrap_pyfunction!(map_from_entries))?;
+    m.add_wrapped(wrap_pyfunction!(zip_with))?;
+    m.add_wrapped(wrap_pyfunction!(array_filter))?;
+    m.add_wrapped(wrap_pyfunction!(array_transform))?;
     m.add_wrapped(wrap_pyfunction!(md5))?;
     m.add_wrapped(wrap_pyfunction!(mean))?;
     m.add_wrapped(wrap_pyfunction!(median))?;
@@ -750,6 +1544,7 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(nanvl))?;
     m.add_wrapped(wrap_pyfunction!(now))?;
     m.add_wrapped(wrap_pyfunction!(nullif))?;
+    m.add_wrapped(wrap_pyfunction!(nvl2))?;
     m.add_wrapped(wrap_pyfunction!(octet_length))?;
     m.add_wrapped(wrap_pyfunction!(order_by))?;
     m.add_wrapped(wrap_pyfunction!(pi))?;
@@ -757,6 +1552,8 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(pow))?;
     m.add_wrapped(wrap_pyfunction!(radians))?;
     m.add_wrapped(wrap_pyfunction!(random))?;
+    m.add_wrapped(wrap_pyfunction!(regexp_count))?;
+    m.add_wrapped(wrap_pyfunction!(regexp_instr))?;
     m.add_wrapped(wrap_pyfunction!(regexp_match))?;
     m.add_wrapped(wrap_pyfunction!(regexp_replace))?;
     m.add_wrapped(wrap_pyfunction!(repeat))?;
@@ -766,6 +1563,7 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(round))?;
     m.add_wrapped(wrap_pyfunction!(rpad))?;
     m.add_wrapped(wrap_pyfunction!(rtrim))?;
+    m.add_wrapped(wrap_pyfunction!(scalar_subquery))?;
     m.add_wrapped(wrap_pyfunction!(sha224))?;
     m.add_wrapped(wrap_pyfunction!(sha256))?;
     m.add_wrapped(wrap_pyfunction!(sha384))?;
@@ -791,6 +1589,14 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(to_timestamp_millis))?;
     m.add_wrapped(wrap_pyfunction!(to_timestamp_micros))?;
     m.add_wrapped(wrap_pyfunction!(to_timestamp_seconds))?;
+    m.add_wrapped(wrap_pyfunction!(to_timestamp_nanos))?;
+    m.add_wrapped(wrap_pyfunction!(to_timestamp_tz))?;
+    m.add_wrapped(wrap_pyfunction!(lit_decimal))?;
+    m.add_wrapped(wrap_pyfunction!(lit_timestamp))?;
+    m.add_wrapped(wrap_pyfunction!(lit_interval))?;
+    m.add_wrapped(wrap_pyfunction!(interval))?;
+    m.add_wrapped(wrap_pyfunction!(date_add))?;
+    m.add_wrapped(wrap_pyfunction!(date_sub))?;
     m.add_wrapped(wrap_pyfunction!(translate))?;
     m.add_wrapped(wrap_pyfunction!(trim))?;
     m.add_wrapped(wrap_pyfunction!(trunc))?;
@@ -800,6 +1606,14 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(var_pop))?;
     m.add_wrapped(wrap_pyfunction!(var_samp))?;
     m.add_wrapped(wrap_pyfunction!(window))?;
+    m.add_wrapped(wrap_pyfunction!(row_number))?;
+    m.add_wrapped(wrap_pyfunction!(rank))?;
+    m.add_wrapped(wrap_pyfunction!(dense_rank))?;
+    m.add_wrapped(wrap_pyfunction!(percent_rank))?;
+    m.add_wrapped(wrap_pyfunction!(cume_dist))?;
+    m.add_wrapped(wrap_pyfunction!(ntile))?;
+    m.add_wrapped(wrap_pyfunction!(lag))?;
+    m.add_wrapped(wrap_pyfunction!(lead))?;
     m.add_wrapped(wrap_pyfunction!(regr_avgx))?;
     m.add_wrapped(wrap_pyfunction!(regr_avgy))?;
     m.add_wrapped(wrap_pyfunction!(regr_count))?;
@@ -883,6 +1697,8 @@ pub(crate) fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(array_slice))?;
     m.add_wrapped(wrap_pyfunction!(list_slice))?;
     m.add_wrapped(wrap_pyfunction!(flatten))?;
+    m.add_wrapped(wrap_pyfunction!(array_flatten))?;
+    m.add_wrapped(wrap_pyfunction!(array_flatten_deep))?;
 
     Ok(())
 }
@@ -0,0 +1,124 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `array_filter(array, func)` keeps the elements of a list for which a boolean
+//! scalar function returns true, in their original order. Elements for which it
+//! returns false or NULL are dropped, and the element type is unchanged.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, ListArray, UInt64Array};
+use datafusion::arrow::buffer::OffsetBuffer;
+use datafusion::arrow::compute::{cast, take};
+use datafusion::arrow::datatypes::DataType;
+use datafusion_common::cast::{as_boolean_array, as_list_array};
+use datafusion_common::{plan_err, Result, ScalarValue};
+use datafusion_expr::type_coercion::functions::data_types;
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature};
+
+#[derive(Debug)]
+struct ArrayFilter {
+    func: Arc<ScalarUDF>,
+    signature: Signature,
+}
+
+impl ArrayFilter {
+    /// Type the elements are cast to before calling the function
+    fn element_type(&self, element: &DataType) -> Result<DataType> {
+        Ok(data_types(std::slice::from_ref(element), self.func.signature())?.remove(0))
+    }
+}
+
+impl ScalarUDFImpl for ArrayFilter {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_filter"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        let DataType::List(field) = &arg_types[0] else {
+            return plan_err!("array_filter expects a list, got {}", arg_types[0]);
+        };
+        let element_type = self.element_type(field.data_type())?;
+        match self.func.inner().return_type(&[element_type])? {
+            DataType::Boolean => Ok(arg_types[0].clone()),
+            other => plan_err!(
+                "array_filter expects a function returning a boolean, {} returns {other}",
+                self.func.name()
+            ),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = matches!(args[0], ColumnarValue::Scalar(_));
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let list = as_list_array(&arrays[0])?;
+
+        let element_type = self.element_type(&list.value_type())?;
+        let keep = self
+            .func
+            .invoke(&[ColumnarValue::Array(cast(list.values(), &element_type)?)])?
+            .into_array(list.values().len())?;
+        let keep = as_boolean_array(&keep)?;
+
+        let mut indices = vec![];
+        let mut offsets = vec![0_i32];
+        for i in 0..list.len() {
+            if list.is_valid(i) {
+                let (start, end) = (list.offsets()[i] as usize, list.offsets()[i + 1] as usize);
+                indices.extend(
+                    (start..end)
+                        .filter(|&j| keep.is_valid(j) && keep.value(j))
+                        .map(|j| j as u64),
+                );
+            }
+            offsets.push(indices.len() as i32);
+        }
+
+        let values = take(list.values(), &UInt64Array::from(indices), None)?;
+        let field = match list.data_type() {
+            DataType::List(field) => field.clone(),
+            _ => unreachable!(),
+        };
+        let result: ArrayRef = Arc::new(ListArray::try_new(
+            field,
+            OffsetBuffer::new(offsets.into()),
+            values,
+            list.nulls().cloned(),
+        )?);
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+pub(crate) fn array_filter(func: Arc<ScalarUDF>) -> ScalarUDF {
+    ScalarUDF::new_from_impl(ArrayFilter {
+        signature: Signature::any(1, func.signature().volatility),
+        func,
+    })
+}
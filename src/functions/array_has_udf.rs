@@ -0,0 +1,147 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Variants of `array_has`, `array_has_all` and `array_has_any` where a NULL
+//! element is compared like any other value, so searching for NULL checks whether
+//! the array contains a NULL. The SQL semantics, where searching for NULL is
+//! unknown, are those of the DataFusion builtins.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, BooleanArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion_common::cast::as_list_array;
+use datafusion_common::{plan_err, Result, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+
+#[derive(Debug, Clone, Copy)]
+enum Search {
+    /// the array contains the element
+    Element,
+    /// the array contains every element of the second array
+    All,
+    /// the array contains at least one element of the second array
+    Any,
+}
+
+#[derive(Debug)]
+struct ArrayHasNullMatching {
+    name: &'static str,
+    search: Search,
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for ArrayHasNullMatching {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match &arg_types[0] {
+            DataType::List(_) | DataType::LargeList(_) => Ok(DataType::Boolean),
+            other => plan_err!(
+                "{} expects a list as first argument, got {other}",
+                self.name
+            ),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = args.iter().all(|a| matches!(a, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let haystacks = match arrays[0].data_type() {
+            DataType::LargeList(field) => cast(&arrays[0], &DataType::List(field.clone()))?,
+            _ => arrays[0].clone(),
+        };
+        let haystacks = as_list_array(&haystacks)?;
+
+        // compare values of the same type, the element (or elements) are cast to the
+        // element type of the searched array
+        let needles = match self.search {
+            Search::Element => cast(&arrays[1], &haystacks.value_type())?,
+            Search::All | Search::Any => cast(&arrays[1], haystacks.data_type())?,
+        };
+
+        let result = (0..haystacks.len())
+            .map(|i| {
+                if haystacks.is_null(i) {
+                    return Ok(None);
+                }
+                let haystack = to_scalars(&haystacks.value(i))?;
+                match self.search {
+                    Search::Element => {
+                        let needle = ScalarValue::try_from_array(&needles, i)?;
+                        Ok(Some(haystack.contains(&needle)))
+                    }
+                    Search::All | Search::Any => {
+                        if needles.is_null(i) {
+                            return Ok(None);
+                        }
+                        let needles = to_scalars(&as_list_array(&needles)?.value(i))?;
+                        let mut found = needles.iter().map(|n| haystack.contains(n));
+                        Ok(Some(match self.search {
+                            Search::All => found.all(|f| f),
+                            _ => found.any(|f| f),
+                        }))
+                    }
+                }
+            })
+            .collect::<Result<BooleanArray>>()?;
+
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(Arc::new(result)))
+        }
+    }
+}
+
+fn to_scalars(array: &ArrayRef) -> Result<Vec<ScalarValue>> {
+    (0..array.len())
+        .map(|i| ScalarValue::try_from_array(array, i))
+        .collect()
+}
+
+fn null_matching_udf(name: &'static str, search: Search) -> ScalarUDF {
+    ScalarUDF::new_from_impl(ArrayHasNullMatching {
+        name,
+        search,
+        signature: Signature::any(2, Volatility::Immutable),
+    })
+}
+
+pub(crate) fn array_has_null_matching() -> ScalarUDF {
+    null_matching_udf("array_has_null_matching", Search::Element)
+}
+
+pub(crate) fn array_has_all_null_matching() -> ScalarUDF {
+    null_matching_udf("array_has_all_null_matching", Search::All)
+}
+
+pub(crate) fn array_has_any_null_matching() -> ScalarUDF {
+    null_matching_udf("array_has_any_null_matching", Search::Any)
+}
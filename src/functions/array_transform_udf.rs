@@ -0,0 +1,100 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `array_transform(array, func)` applies a scalar function to each element of a
+//! list, keeping their order. NULL elements are passed to the function like any
+//! other, and the element type of the result is the return type of the function.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, ListArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion_common::cast::as_list_array;
+use datafusion_common::{plan_err, Result, ScalarValue};
+use datafusion_expr::type_coercion::functions::data_types;
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature};
+
+#[derive(Debug)]
+struct ArrayTransform {
+    func: Arc<ScalarUDF>,
+    signature: Signature,
+}
+
+impl ArrayTransform {
+    /// Type the elements are cast to before calling the function
+    fn element_type(&self, element: &DataType) -> Result<DataType> {
+        Ok(data_types(std::slice::from_ref(element), self.func.signature())?.remove(0))
+    }
+}
+
+impl ScalarUDFImpl for ArrayTransform {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_transform"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        let DataType::List(field) = &arg_types[0] else {
+            return plan_err!("array_transform expects a list, got {}", arg_types[0]);
+        };
+        let element_type = self.element_type(field.data_type())?;
+        Ok(DataType::new_list(
+            self.func.inner().return_type(&[element_type])?,
+            true,
+        ))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = matches!(args[0], ColumnarValue::Scalar(_));
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let list = as_list_array(&arrays[0])?;
+
+        let element_type = self.element_type(&list.value_type())?;
+        let values = self
+            .func
+            .invoke(&[ColumnarValue::Array(cast(list.values(), &element_type)?)])?
+            .into_array(list.values().len())?;
+
+        let result: ArrayRef = Arc::new(ListArray::try_new(
+            Arc::new(Field::new("item", values.data_type().clone(), true)),
+            list.offsets().clone(),
+            values,
+            list.nulls().cloned(),
+        )?);
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+pub(crate) fn array_transform(func: Arc<ScalarUDF>) -> ScalarUDF {
+    ScalarUDF::new_from_impl(ArrayTransform {
+        signature: Signature::any(1, func.signature().volatility),
+        func,
+    })
+}
@@ -0,0 +1,98 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `fill_null(expr, value)` replaces the NULLs of `expr` with `value`.
+//!
+//! Unlike `coalesce` both arguments must be of compatible kinds: numbers are
+//! widened to a common type, but filling a string with a number (or the other
+//! way around) is an error instead of an implicit cast.
+
+use std::any::Any;
+
+use datafusion::arrow::compute::{is_not_null, kernels::zip::zip};
+use datafusion::arrow::datatypes::DataType;
+use datafusion_common::{plan_err, Result, ScalarValue};
+use datafusion_expr::type_coercion::binary::comparison_coercion;
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+
+fn is_string(data_type: &DataType) -> bool {
+    matches!(data_type, DataType::Utf8 | DataType::LargeUtf8)
+}
+
+/// The type two values of the same kind are widened to: numbers to a common numeric
+/// type, strings to a common string type and temporal values to a common temporal
+/// type. NULL takes the type of the other value. Values of different kinds have no
+/// common type.
+pub(crate) fn common_value_type(a: &DataType, b: &DataType) -> Option<DataType> {
+    match (a, b) {
+        _ if a == b => Some(a.clone()),
+        (_, DataType::Null) => Some(a.clone()),
+        (DataType::Null, _) => Some(b.clone()),
+        _ if a.is_numeric() && b.is_numeric() => comparison_coercion(a, b),
+        _ if is_string(a) && is_string(b) => comparison_coercion(a, b),
+        _ if a.is_temporal() && b.is_temporal() => comparison_coercion(a, b),
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
+struct FillNull {
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for FillNull {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "fill_null"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let (expr, value) = (&arg_types[0], &arg_types[1]);
+        match common_value_type(expr, value) {
+            Some(data_type) => Ok(vec![data_type.clone(), data_type]),
+            None => plan_err!("Cannot fill nulls of type {expr} with a value of type {value}"),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = args.iter().all(|a| matches!(a, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let result = zip(&is_not_null(&arrays[0])?, &arrays[0], &arrays[1])?;
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+pub(crate) fn fill_null() -> ScalarUDF {
+    ScalarUDF::new_from_impl(FillNull {
+        signature: Signature::user_defined(Volatility::Immutable),
+    })
+}
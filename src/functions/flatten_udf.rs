@@ -0,0 +1,152 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `array_flatten_deep(array)` flattens lists nested to any depth into a single
+//! list of the innermost elements, keeping their order.
+//!
+//! A NULL top level list gives NULL, NULL lists below it contribute no elements
+//! and NULL elements at the innermost level are kept.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, AsArray, ListArray, UInt64Array};
+use datafusion::arrow::buffer::OffsetBuffer;
+use datafusion::arrow::compute::take;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion_common::{plan_err, Result, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+
+/// Returns the values of a list array and the range of row `i` within them, or
+/// `None` if `array` is not a list
+fn list_range(array: &dyn Array, i: usize) -> Option<(&ArrayRef, usize, usize)> {
+    match array.data_type() {
+        DataType::List(_) => {
+            let list = array.as_list::<i32>();
+            let offsets = list.value_offsets();
+            Some((list.values(), offsets[i] as usize, offsets[i + 1] as usize))
+        }
+        DataType::LargeList(_) => {
+            let list = array.as_list::<i64>();
+            let offsets = list.value_offsets();
+            Some((list.values(), offsets[i] as usize, offsets[i + 1] as usize))
+        }
+        DataType::FixedSizeList(_, _) => {
+            let list = array.as_fixed_size_list();
+            let start = list.value_offset(i) as usize;
+            Some((list.values(), start, start + list.value_length() as usize))
+        }
+        _ => None,
+    }
+}
+
+/// Appends the position in the innermost values of every element of `values`
+/// between `start` and `end`
+fn collect_elements(values: &ArrayRef, start: usize, end: usize, indices: &mut Vec<u64>) {
+    for i in start..end {
+        match list_range(values.as_ref(), i) {
+            Some((child, child_start, child_end)) => {
+                if values.is_valid(i) {
+                    collect_elements(child, child_start, child_end, indices)
+                }
+            }
+            None => indices.push(i as u64),
+        }
+    }
+}
+
+fn innermost_values(array: &ArrayRef) -> &ArrayRef {
+    let values = match array.data_type() {
+        DataType::List(_) => array.as_list::<i32>().values(),
+        DataType::LargeList(_) => array.as_list::<i64>().values(),
+        DataType::FixedSizeList(_, _) => array.as_fixed_size_list().values(),
+        _ => return array,
+    };
+    innermost_values(values)
+}
+
+fn innermost_type(data_type: &DataType) -> &DataType {
+    match data_type {
+        DataType::List(field) | DataType::LargeList(field) | DataType::FixedSizeList(field, _) => {
+            innermost_type(field.data_type())
+        }
+        other => other,
+    }
+}
+
+#[derive(Debug)]
+struct FlattenDeep {
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for FlattenDeep {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_flatten_deep"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match &arg_types[0] {
+            DataType::List(_) | DataType::LargeList(_) | DataType::FixedSizeList(_, _) => Ok(
+                DataType::new_list(innermost_type(&arg_types[0]).clone(), true),
+            ),
+            other => plan_err!("array_flatten_deep expects a list, got {other}"),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = matches!(args[0], ColumnarValue::Scalar(_));
+        let array = args[0].clone().into_array(1)?;
+
+        let mut indices = vec![];
+        let mut offsets = vec![0_i32];
+        for i in 0..array.len() {
+            if array.is_valid(i) {
+                if let Some((values, start, end)) = list_range(array.as_ref(), i) {
+                    collect_elements(values, start, end, &mut indices);
+                }
+            }
+            offsets.push(indices.len() as i32);
+        }
+        let values = take(innermost_values(&array), &UInt64Array::from(indices), None)?;
+
+        let result: ArrayRef = Arc::new(ListArray::try_new(
+            Arc::new(Field::new("item", values.data_type().clone(), true)),
+            OffsetBuffer::new(offsets.into()),
+            values,
+            array.logical_nulls(),
+        )?);
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+pub(crate) fn array_flatten_deep() -> ScalarUDF {
+    ScalarUDF::new_from_impl(FlattenDeep {
+        signature: Signature::any(1, Volatility::Immutable),
+    })
+}
@@ -0,0 +1,84 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `get_field(expr, name)` reads a field of a struct, like the builtin function of
+//! the same name which it delegates to, but a field that does not exist is reported
+//! together with the fields the struct does have.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::DataType;
+use datafusion::functions::core::get_field as builtin_get_field;
+use datafusion_common::{plan_err, ExprSchema, Result, ScalarValue};
+use datafusion_expr::{ColumnarValue, Expr, ScalarUDF, ScalarUDFImpl, Signature};
+
+#[derive(Debug)]
+struct GetField {
+    inner: Arc<ScalarUDF>,
+}
+
+impl ScalarUDFImpl for GetField {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        self.inner.signature()
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        self.inner.inner().return_type(arg_types)
+    }
+
+    fn return_type_from_exprs(
+        &self,
+        args: &[Expr],
+        schema: &dyn ExprSchema,
+        arg_types: &[DataType],
+    ) -> Result<DataType> {
+        if let (DataType::Struct(fields), Some(Expr::Literal(ScalarValue::Utf8(Some(name))))) =
+            (&arg_types[0], args.get(1))
+        {
+            if fields.find(name).is_none() {
+                let available = fields
+                    .iter()
+                    .map(|f| f.name().as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return plan_err!(
+                    "Field '{name}' not found in struct, available fields are: {available}"
+                );
+            }
+        }
+        self.inner.return_type_from_exprs(args, schema, arg_types)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        self.inner.invoke(args)
+    }
+}
+
+pub(crate) fn get_field() -> ScalarUDF {
+    ScalarUDF::new_from_impl(GetField {
+        inner: builtin_get_field(),
+    })
+}
@@ -0,0 +1,318 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Scalar functions building and reading `Map` values, which DataFusion does not
+//! provide yet.
+//!
+//! Map keys must not be null and must be unique within each map: a null or
+//! duplicate key raises an execution error rather than being silently dropped.
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, MapArray, StructArray, UInt64Array};
+use datafusion::arrow::buffer::{NullBuffer, OffsetBuffer};
+use datafusion::arrow::compute::{cast, take};
+use datafusion::arrow::datatypes::{DataType, Field, Fields};
+use datafusion_common::cast::{as_list_array, as_map_array, as_struct_array};
+use datafusion_common::{exec_err, plan_err, Result, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+
+/// `map_from_arrays(keys, values)` pairs up the elements of two lists, also
+/// registered as `map(keys, values)`
+#[derive(Debug)]
+struct MapFromArrays {
+    name: &'static str,
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for MapFromArrays {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match (&arg_types[0], &arg_types[1]) {
+            (DataType::List(keys), DataType::List(values)) => {
+                Ok(map_type(keys.data_type(), values.data_type()))
+            }
+            (keys, values) => {
+                plan_err!("{} expects two lists, got {keys} and {values}", self.name)
+            }
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = args.iter().all(|a| matches!(a, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let keys = as_list_array(&arrays[0])?;
+        let values = as_list_array(&arrays[1])?;
+
+        let rows = (0..keys.len())
+            .map(|i| {
+                if keys.is_null(i) || values.is_null(i) {
+                    return Ok(None);
+                }
+                let (key_range, value_range) = (
+                    list_range(keys.offsets(), i),
+                    list_range(values.offsets(), i),
+                );
+                if key_range.len() != value_range.len() {
+                    return exec_err!(
+                        "{} expects as many keys as values, got {} keys and {} values",
+                        self.name,
+                        key_range.len(),
+                        value_range.len()
+                    );
+                }
+                Ok(Some((key_range, value_range)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let map = build_map(self.name(), keys.values(), values.values(), &rows)?;
+        to_columnar_value(map, is_scalar)
+    }
+}
+
+/// `map_from_entries(entries)` builds a map from a list of `(key, value)` structs
+#[derive(Debug)]
+struct MapFromEntries {
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for MapFromEntries {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "map_from_entries"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match &arg_types[0] {
+            DataType::List(entry) => match entry.data_type() {
+                DataType::Struct(fields) if fields.len() == 2 => {
+                    Ok(map_type(fields[0].data_type(), fields[1].data_type()))
+                }
+                other => {
+                    plan_err!("map_from_entries expects a list of key-value structs, got {other}")
+                }
+            },
+            other => plan_err!("map_from_entries expects a list of structs, got {other}"),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = matches!(args[0], ColumnarValue::Scalar(_));
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let entries = as_list_array(&arrays[0])?;
+        let structs = as_struct_array(entries.values())?;
+
+        let rows = (0..entries.len())
+            .map(|i| {
+                if entries.is_null(i) {
+                    return Ok(None);
+                }
+                let range = list_range(entries.offsets(), i);
+                if range.clone().any(|idx| structs.is_null(idx)) {
+                    return exec_err!("map_from_entries does not accept null entries");
+                }
+                Ok(Some((range.clone(), range)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let map = build_map(self.name(), structs.column(0), structs.column(1), &rows)?;
+        to_columnar_value(map, is_scalar)
+    }
+}
+
+/// `map_extract(map, key)` returns the value of `key` in the map, or NULL when the
+/// map does not contain it
+#[derive(Debug)]
+struct MapExtract {
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for MapExtract {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "map_extract"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match &arg_types[0] {
+            DataType::Map(field, _) => match field.data_type() {
+                DataType::Struct(fields) if fields.len() == 2 => Ok(fields[1].data_type().clone()),
+                other => plan_err!("map_extract expects map entries to be structs, got {other}"),
+            },
+            other => plan_err!("map_extract expects a map, got {other}"),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = args.iter().all(|a| matches!(a, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let map = as_map_array(&arrays[0])?;
+        let keys = map.keys();
+        let lookup = cast(&arrays[1], keys.data_type())?;
+
+        let mut value_indices = vec![];
+        for i in 0..map.len() {
+            let mut found = None;
+            if map.is_valid(i) && lookup.is_valid(i) {
+                let key = ScalarValue::try_from_array(&lookup, i)?;
+                for idx in list_range(map.offsets(), i) {
+                    if ScalarValue::try_from_array(keys, idx)? == key {
+                        found = Some(idx as u64);
+                        break;
+                    }
+                }
+            }
+            value_indices.push(found);
+        }
+
+        let values = take(
+            map.values().as_ref(),
+            &UInt64Array::from(value_indices),
+            None,
+        )?;
+        to_columnar_value(values, is_scalar)
+    }
+}
+
+pub(crate) fn map_from_arrays_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(MapFromArrays {
+        name: "map_from_arrays",
+        signature: Signature::any(2, Volatility::Immutable),
+    })
+}
+
+pub(crate) fn map_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(MapFromArrays {
+        name: "map",
+        signature: Signature::any(2, Volatility::Immutable),
+    })
+}
+
+pub(crate) fn map_extract_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(MapExtract {
+        signature: Signature::any(2, Volatility::Immutable),
+    })
+}
+
+pub(crate) fn map_from_entries_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(MapFromEntries {
+        signature: Signature::any(1, Volatility::Immutable),
+    })
+}
+
+fn map_type(key: &DataType, value: &DataType) -> DataType {
+    let entries = Fields::from(vec![
+        Field::new("key", key.clone(), false),
+        Field::new("value", value.clone(), true),
+    ]);
+    DataType::Map(
+        Arc::new(Field::new("entries", DataType::Struct(entries), false)),
+        false,
+    )
+}
+
+fn list_range(offsets: &OffsetBuffer<i32>, i: usize) -> Range<usize> {
+    offsets[i] as usize..offsets[i + 1] as usize
+}
+
+/// Builds one map per row from the given ranges of `keys` and `values`, a row
+/// without ranges is a null map.
+fn build_map(
+    name: &str,
+    keys: &ArrayRef,
+    values: &ArrayRef,
+    rows: &[Option<(Range<usize>, Range<usize>)>],
+) -> Result<ArrayRef> {
+    let mut key_indices = vec![];
+    let mut value_indices = vec![];
+    let mut offsets = vec![0_i32];
+    let mut validity = vec![];
+
+    for row in rows {
+        if let Some((key_range, value_range)) = row {
+            let mut seen = HashSet::new();
+            for idx in key_range.clone() {
+                if keys.is_null(idx) {
+                    return exec_err!("{name} does not accept null keys");
+                }
+                let key = ScalarValue::try_from_array(keys, idx)?;
+                if seen.contains(&key) {
+                    return exec_err!("{name} found duplicate key {key}");
+                }
+                seen.insert(key);
+            }
+            key_indices.extend(key_range.clone().map(|idx| idx as u64));
+            value_indices.extend(value_range.clone().map(|idx| idx as u64));
+        }
+        offsets.push(key_indices.len() as i32);
+        validity.push(row.is_some());
+    }
+
+    let keys = take(keys.as_ref(), &UInt64Array::from(key_indices), None)?;
+    let values = take(values.as_ref(), &UInt64Array::from(value_indices), None)?;
+    let DataType::Map(field, _) = map_type(keys.data_type(), values.data_type()) else {
+        unreachable!()
+    };
+    let DataType::Struct(fields) = field.data_type() else {
+        unreachable!()
+    };
+    let entries = StructArray::try_new(fields.clone(), vec![keys, values], None)?;
+    let map = MapArray::try_new(
+        field,
+        OffsetBuffer::new(offsets.into()),
+        entries,
+        Some(NullBuffer::from(validity)),
+        false,
+    )?;
+    Ok(Arc::new(map))
+}
+
+fn to_columnar_value(array: ArrayRef, is_scalar: bool) -> Result<ColumnarValue> {
+    if is_scalar {
+        ScalarValue::try_from_array(&array, 0).map(ColumnarValue::Scalar)
+    } else {
+        Ok(ColumnarValue::Array(array))
+    }
+}
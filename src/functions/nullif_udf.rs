@@ -0,0 +1,103 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `nullif_nan(expr)` and `nullif_inf(expr)` turn the NaN, respectively the positive
+//! and negative infinite, values of a float column into NULLs. Other values,
+//! NULLs included, are kept as they are.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, Float32Array, Float64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion_common::cast::{as_float32_array, as_float64_array};
+use datafusion_common::{plan_err, Result, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+
+#[derive(Debug)]
+struct NullIf {
+    name: &'static str,
+    matches_f32: fn(f32) -> bool,
+    matches_f64: fn(f64) -> bool,
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for NullIf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match &arg_types[0] {
+            DataType::Float32 | DataType::Float64 => Ok(arg_types[0].clone()),
+            other => plan_err!("{} expects a float column, got {other}", self.name),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = matches!(args[0], ColumnarValue::Scalar(_));
+        let array = ColumnarValue::values_to_arrays(args)?.remove(0);
+        let result: ArrayRef = match array.data_type() {
+            DataType::Float32 => Arc::new(
+                as_float32_array(&array)?
+                    .iter()
+                    .map(|v| v.filter(|x| !(self.matches_f32)(*x)))
+                    .collect::<Float32Array>(),
+            ),
+            _ => Arc::new(
+                as_float64_array(&array)?
+                    .iter()
+                    .map(|v| v.filter(|x| !(self.matches_f64)(*x)))
+                    .collect::<Float64Array>(),
+            ),
+        };
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+fn null_if(
+    name: &'static str,
+    matches_f32: fn(f32) -> bool,
+    matches_f64: fn(f64) -> bool,
+) -> ScalarUDF {
+    ScalarUDF::new_from_impl(NullIf {
+        name,
+        matches_f32,
+        matches_f64,
+        signature: Signature::any(1, Volatility::Immutable),
+    })
+}
+
+pub(crate) fn nullif_nan() -> ScalarUDF {
+    null_if("nullif_nan", f32::is_nan, f64::is_nan)
+}
+
+pub(crate) fn nullif_inf() -> ScalarUDF {
+    null_if("nullif_inf", f32::is_infinite, f64::is_infinite)
+}
@@ -0,0 +1,76 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The builtin `nvl2(test, if_not_null, if_null)`, with a clear error when the
+//! arguments have no common type.
+
+use std::any::Any;
+
+use datafusion::arrow::datatypes::DataType;
+use datafusion::functions::core::nvl2::NVL2Func;
+use datafusion_common::{plan_err, Result};
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature};
+
+#[derive(Debug)]
+struct Nvl2 {
+    inner: NVL2Func,
+}
+
+impl ScalarUDFImpl for Nvl2 {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        self.inner.signature()
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        self.inner.return_type(arg_types)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        match self.inner.coerce_types(arg_types) {
+            Ok(types) => Ok(types),
+            Err(_) if arg_types.len() == 3 => {
+                let types = arg_types
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                plan_err!(
+                    "nvl2 cannot combine arguments of types {types}, cast them to a common type"
+                )
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        self.inner.invoke(args)
+    }
+}
+
+pub(crate) fn nvl2() -> ScalarUDF {
+    ScalarUDF::new_from_impl(Nvl2 {
+        inner: NVL2Func::new(),
+    })
+}
@@ -0,0 +1,350 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Aggregates that collect the values of a group and turn the list into a single
+//! value, following the `ORDER BY` of the call. `json_agg` encodes the values as a
+//! JSON array, `string_agg` joins them with a delimiter and the DISTINCT `array_agg`
+//! keeps each value once.
+//!
+//! The values are sorted when the group is finished rather than relying on sorted
+//! input, so partial results from several partitions merge in the right order.
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::compute::SortOptions;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion_common::cast::as_list_array;
+use datafusion_common::utils::compare_rows;
+use datafusion_common::{exec_err, DFSchema, Result, ScalarValue};
+use datafusion_expr::expr::Sort;
+use datafusion_expr::expr_rewriter::unnormalize_col;
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::{format_state_name, AggregateOrderSensitivity};
+use datafusion_expr::{
+    Accumulator, AggregateUDF, AggregateUDFImpl, ColumnarValue, Expr, ExprSchemable, Signature,
+    Volatility,
+};
+
+use super::to_json::to_json;
+
+/// How the sorted values of a group become the result
+#[derive(Debug, Clone, Copy)]
+enum Finish {
+    Json,
+    /// Joins the non-NULL values with the delimiter given as the second argument
+    Join,
+    /// The unique values as an array, the ordering being the value itself
+    DistinctArray,
+}
+
+#[derive(Debug)]
+struct OrderedAgg {
+    name: &'static str,
+    finish: Finish,
+    signature: Signature,
+}
+
+impl AggregateUDFImpl for OrderedAgg {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match self.finish {
+            Finish::Json | Finish::Join => Ok(DataType::Utf8),
+            Finish::DistinctArray => Ok(DataType::new_list(arg_types[0].clone(), true)),
+        }
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(OrderedAccumulator::try_new(
+            self.finish,
+            &acc_args,
+        )?))
+    }
+
+    /// The values, then one list per `ORDER BY` expression holding its value for
+    /// each of them, and the delimiter when joining
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<Field>> {
+        let mut fields = vec![Field::new_list(
+            format_state_name(args.name, "values"),
+            Field::new("item", args.input_type.clone(), true),
+            true,
+        )];
+        for (i, field) in args.ordering_fields.iter().enumerate() {
+            fields.push(Field::new_list(
+                format_state_name(args.name, &format!("ordering_{i}")),
+                Field::new("item", field.data_type().clone(), true),
+                true,
+            ));
+        }
+        if let Finish::Join = self.finish {
+            fields.push(Field::new(
+                format_state_name(args.name, "delimiter"),
+                DataType::Utf8,
+                true,
+            ));
+        }
+        Ok(fields)
+    }
+
+    /// The values are sorted by the accumulator, so sorted input only saves work
+    fn order_sensitivity(&self) -> AggregateOrderSensitivity {
+        AggregateOrderSensitivity::Beneficial
+    }
+
+    fn with_beneficial_ordering(
+        self: Arc<Self>,
+        _beneficial_ordering: bool,
+    ) -> Result<Option<Arc<dyn AggregateUDFImpl>>> {
+        Ok(Some(self))
+    }
+}
+
+#[derive(Debug)]
+struct OrderedAccumulator {
+    finish: Finish,
+    value_type: DataType,
+    ordering_types: Vec<DataType>,
+    sort_options: Vec<SortOptions>,
+    values: Vec<ScalarValue>,
+    orderings: Vec<Vec<ScalarValue>>,
+    delimiter: Option<String>,
+}
+
+impl OrderedAccumulator {
+    fn try_new(finish: Finish, args: &AccumulatorArgs) -> Result<Self> {
+        let schema = DFSchema::try_from(args.schema.clone())?;
+        let mut ordering_types = vec![];
+        let mut sort_options = vec![];
+        for expr in args.sort_exprs {
+            let Expr::Sort(Sort {
+                expr,
+                asc,
+                nulls_first,
+            }) = expr
+            else {
+                return exec_err!("Expected a sort expression, got {expr}");
+            };
+            // the input schema of the aggregation has no qualifiers
+            ordering_types.push(unnormalize_col(expr.as_ref().clone()).get_type(&schema)?);
+            sort_options.push(SortOptions {
+                descending: !asc,
+                nulls_first: *nulls_first,
+            });
+        }
+        Ok(Self {
+            finish,
+            value_type: args.input_type.clone(),
+            ordering_types,
+            sort_options,
+            values: vec![],
+            orderings: vec![],
+            delimiter: None,
+        })
+    }
+
+    /// Adds the rows of `values` and of the matching `orderings`
+    fn append(&mut self, values: &ArrayRef, orderings: &[ArrayRef]) -> Result<()> {
+        for row in 0..values.len() {
+            self.values.push(ScalarValue::try_from_array(values, row)?);
+            self.orderings.push(
+                orderings
+                    .iter()
+                    .map(|ordering| ScalarValue::try_from_array(ordering, row))
+                    .collect::<Result<_>>()?,
+            );
+        }
+        Ok(())
+    }
+
+    /// Keeps the first non-NULL delimiter of `delimiters`
+    fn set_delimiter(&mut self, delimiters: &ArrayRef) -> Result<()> {
+        if self.delimiter.is_some() {
+            return Ok(());
+        }
+        for row in 0..delimiters.len() {
+            match ScalarValue::try_from_array(delimiters, row)? {
+                ScalarValue::Utf8(Some(delimiter)) | ScalarValue::LargeUtf8(Some(delimiter)) => {
+                    self.delimiter = Some(delimiter);
+                    break;
+                }
+                ScalarValue::Utf8(None) | ScalarValue::LargeUtf8(None) | ScalarValue::Null => {}
+                other => return exec_err!("The delimiter must be a string, got {other:?}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// The values in the order of the `ORDER BY`, or as received without one
+    fn sorted_values(&self) -> Result<Vec<ScalarValue>> {
+        let mut indices = (0..self.values.len()).collect::<Vec<_>>();
+        let mut error = None;
+        indices.sort_by(|&a, &b| {
+            compare_rows(&self.orderings[a], &self.orderings[b], &self.sort_options).unwrap_or_else(
+                |e| {
+                    error.get_or_insert(e);
+                    Ordering::Equal
+                },
+            )
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        Ok(indices
+            .into_iter()
+            .map(|i| self.values[i].clone())
+            .collect())
+    }
+}
+
+impl Accumulator for OrderedAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        if let Finish::Join = self.finish {
+            self.set_delimiter(&values[1])?;
+        }
+        let orderings = &values[values.len() - self.sort_options.len()..];
+        self.append(&values[0], orderings)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let lists = states[..1 + self.sort_options.len()]
+            .iter()
+            .map(|state| as_list_array(state))
+            .collect::<Result<Vec<_>>>()?;
+        for row in 0..states[0].len() {
+            if lists[0].is_null(row) {
+                continue;
+            }
+            let orderings = lists[1..]
+                .iter()
+                .map(|list| list.value(row))
+                .collect::<Vec<_>>();
+            self.append(&lists[0].value(row), &orderings)?;
+        }
+        if let Finish::Join = self.finish {
+            self.set_delimiter(&states[states.len() - 1])?;
+        }
+        Ok(())
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        let mut state = vec![ScalarValue::List(ScalarValue::new_list(
+            &self.values,
+            &self.value_type,
+        ))];
+        for (i, data_type) in self.ordering_types.iter().enumerate() {
+            let ordering = self
+                .orderings
+                .iter()
+                .map(|row| row[i].clone())
+                .collect::<Vec<_>>();
+            state.push(ScalarValue::List(ScalarValue::new_list(
+                &ordering, data_type,
+            )));
+        }
+        if let Finish::Join = self.finish {
+            state.push(ScalarValue::Utf8(self.delimiter.clone()));
+        }
+        Ok(state)
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let mut values = self.sorted_values()?;
+        match self.finish {
+            Finish::Json if values.is_empty() => Ok(ScalarValue::Utf8(None)),
+            Finish::Json => {
+                let list = ScalarValue::List(ScalarValue::new_list(&values, &self.value_type));
+                match to_json().invoke(&[ColumnarValue::Scalar(list)])? {
+                    ColumnarValue::Scalar(json) => Ok(json),
+                    ColumnarValue::Array(json) => ScalarValue::try_from_array(&json, 0),
+                }
+            }
+            Finish::Join => {
+                let values = values
+                    .iter()
+                    .filter(|value| !value.is_null())
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                if values.is_empty() {
+                    return Ok(ScalarValue::Utf8(None));
+                }
+                let delimiter = self.delimiter.as_deref().unwrap_or_default();
+                Ok(ScalarValue::Utf8(Some(values.join(delimiter))))
+            }
+            Finish::DistinctArray => {
+                // equal values are next to each other once sorted
+                values.dedup();
+                Ok(ScalarValue::List(ScalarValue::new_list(
+                    &values,
+                    &self.value_type,
+                )))
+            }
+        }
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + ScalarValue::size_of_vec(&self.values)
+            + self.delimiter.as_ref().map_or(0, String::capacity)
+            + self
+                .orderings
+                .iter()
+                .map(ScalarValue::size_of_vec)
+                .sum::<usize>()
+    }
+}
+
+/// `json_agg(expr)`: the values of the group as a JSON array string
+pub(crate) fn json_agg() -> AggregateUDF {
+    AggregateUDF::new_from_impl(OrderedAgg {
+        name: "json_agg",
+        finish: Finish::Json,
+        signature: Signature::any(1, Volatility::Immutable),
+    })
+}
+
+/// `string_agg(expr, delimiter)`: the non-NULL values of the group joined by
+/// `delimiter`, or NULL if there are none
+pub(crate) fn string_agg() -> AggregateUDF {
+    AggregateUDF::new_from_impl(OrderedAgg {
+        name: "string_agg",
+        finish: Finish::Join,
+        signature: Signature::any(2, Volatility::Immutable),
+    })
+}
+
+/// `array_agg(DISTINCT expr ORDER BY expr)`: the unique values of the group as an
+/// array, sorted as given by the `ORDER BY` on the aggregated expression
+pub(crate) fn array_agg_distinct() -> AggregateUDF {
+    AggregateUDF::new_from_impl(OrderedAgg {
+        name: "array_agg",
+        finish: Finish::DistinctArray,
+        signature: Signature::any(1, Volatility::Immutable),
+    })
+}
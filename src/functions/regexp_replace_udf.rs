@@ -0,0 +1,220 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `regexp_replace` with an Oracle style start position and occurrence.
+//!
+//! Only the text from the position onward is searched. Occurrence 0 replaces every
+//! match and `n` only the nth one; without an occurrence the `g` flag decides
+//! between the first and all matches.
+
+use std::any::Any;
+use std::collections::{hash_map::Entry, HashMap};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, StringArray};
+use datafusion::arrow::datatypes::DataType;
+use datafusion_common::cast::{as_int64_array, as_string_array};
+use datafusion_common::{exec_err, plan_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+use regex::Regex;
+
+/// `regexp_replace` with a position and an occurrence, see `regexp_replace_nth_impl`
+#[derive(Debug)]
+struct RegexpReplaceNth {
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for RegexpReplaceNth {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_replace_nth"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        regexp_replace_nth_impl(args)
+    }
+
+    /// The string, pattern, replacement and flags are read as Utf8, the position and
+    /// occurrence as Int64
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        if arg_types.len() != 6 {
+            return plan_err!(
+                "regexp_replace_nth expects 6 arguments, got {}",
+                arg_types.len()
+            );
+        }
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(i, arg_type)| match (i < 4, arg_type) {
+                (true, DataType::Utf8 | DataType::LargeUtf8 | DataType::Null) => Ok(DataType::Utf8),
+                (false, DataType::Null) => Ok(DataType::Int64),
+                (false, arg_type) if arg_type.is_integer() => Ok(DataType::Int64),
+                (_, arg_type) => plan_err!(
+                    "regexp_replace_nth does not accept {arg_type} as argument {}",
+                    i + 1
+                ),
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn regexp_replace_nth() -> ScalarUDF {
+    ScalarUDF::new_from_impl(RegexpReplaceNth {
+        signature: Signature::user_defined(Volatility::Immutable),
+    })
+}
+
+fn regexp_replace_nth_impl(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    let is_scalar = args.iter().all(|a| matches!(a, ColumnarValue::Scalar(_)));
+    let arrays = ColumnarValue::values_to_arrays(args)?;
+    let strings = as_string_array(&arrays[0])?;
+    let patterns = as_string_array(&arrays[1])?;
+    let replacements = as_string_array(&arrays[2])?;
+    let flags = as_string_array(&arrays[3])?;
+    let positions = as_int64_array(&arrays[4])?;
+    let occurrences = as_int64_array(&arrays[5])?;
+
+    // `\1` style back references are rewritten to the `${1}` syntax of the regex crate
+    let back_reference = Regex::new(r"\\(\d+)").unwrap();
+    let mut compiled: HashMap<(String, String), Regex> = HashMap::new();
+
+    let result = (0..strings.len())
+        .map(|i| {
+            if strings.is_null(i)
+                || patterns.is_null(i)
+                || replacements.is_null(i)
+                || positions.is_null(i)
+            {
+                return Ok(None);
+            }
+            let flags = if flags.is_null(i) { "" } else { flags.value(i) };
+            let global = flags.contains('g');
+            let flags = flags.replace('g', "");
+
+            let position = positions.value(i);
+            if position < 1 {
+                return exec_err!("regexp_replace position must be at least 1, got {position}");
+            }
+            let occurrence = match (occurrences.is_null(i), global) {
+                (true, true) => 0,
+                (true, false) => 1,
+                (false, global) => {
+                    let occurrence = occurrences.value(i);
+                    if occurrence < 0 {
+                        return exec_err!(
+                            "regexp_replace occurrence must not be negative, got {occurrence}"
+                        );
+                    }
+                    if global && occurrence != 0 {
+                        return exec_err!(
+                            "regexp_replace cannot combine the 'g' flag with occurrence {occurrence}"
+                        );
+                    }
+                    occurrence
+                }
+            };
+
+            let regex = cached_regex(&mut compiled, patterns.value(i), flags)?;
+            let replacement = back_reference.replace_all(replacements.value(i), "$${$1}");
+
+            Ok(Some(replace_nth(
+                strings.value(i),
+                regex,
+                &replacement,
+                position as usize,
+                occurrence as usize,
+            )))
+        })
+        .collect::<Result<StringArray>>()?;
+
+    if is_scalar {
+        ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+    } else {
+        Ok(ColumnarValue::Array(Arc::new(result)))
+    }
+}
+
+/// Replaces the nth match (or all matches when `occurrence` is 0) of `regex`
+/// found at or after the 1-based character `position` of `value`.
+fn replace_nth(
+    value: &str,
+    regex: &Regex,
+    replacement: &str,
+    position: usize,
+    occurrence: usize,
+) -> String {
+    let (head, tail) = value.split_at(char_offset(value, position));
+
+    if occurrence == 0 {
+        return format!("{head}{}", regex.replace_all(tail, replacement));
+    }
+    match regex.captures_iter(tail).nth(occurrence - 1) {
+        Some(captures) => {
+            let matched = captures.get(0).unwrap();
+            let mut result = String::with_capacity(value.len());
+            result.push_str(head);
+            result.push_str(&tail[..matched.start()]);
+            captures.expand(replacement, &mut result);
+            result.push_str(&tail[matched.end()..]);
+            result
+        }
+        None => value.to_string(),
+    }
+}
+
+/// The regex for `pattern` with `flags`, compiled once per distinct pair
+pub(crate) fn cached_regex<'a>(
+    compiled: &'a mut HashMap<(String, String), Regex>,
+    pattern: &str,
+    flags: String,
+) -> Result<&'a Regex> {
+    match compiled.entry((pattern.to_string(), flags)) {
+        Entry::Occupied(entry) => Ok(entry.into_mut()),
+        Entry::Vacant(entry) => {
+            let (pattern, flags) = entry.key();
+            let pattern = if flags.is_empty() {
+                pattern.clone()
+            } else {
+                format!("(?{flags}){pattern}")
+            };
+            let regex = Regex::new(&pattern).map_err(|e| DataFusionError::External(Box::new(e)))?;
+            Ok(entry.insert(regex))
+        }
+    }
+}
+
+/// The byte offset of the 1-based character `position` of `value`, or its length
+/// when `value` is shorter
+pub(crate) fn char_offset(value: &str, position: usize) -> usize {
+    value
+        .char_indices()
+        .nth(position - 1)
+        .map(|(i, _)| i)
+        .unwrap_or(value.len())
+}
@@ -0,0 +1,144 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `regexp_count` and `regexp_instr`, which search for the non-overlapping matches of
+//! a pattern from a 1-based start character.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, Int64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion_common::cast::{as_int64_array, as_string_array};
+use datafusion_common::{exec_err, Result, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+use regex::Regex;
+
+use super::regexp_replace_udf::{cached_regex, char_offset};
+
+/// Both functions take `(string, pattern, start, occurrence, flags)`, `regexp_count`
+/// ignores `occurrence`
+#[derive(Debug)]
+struct RegexpSearch {
+    name: &'static str,
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for RegexpSearch {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        regexp_search_impl(self.name, args)
+    }
+}
+
+fn regexp_search(name: &'static str) -> ScalarUDF {
+    ScalarUDF::new_from_impl(RegexpSearch {
+        name,
+        signature: Signature::exact(
+            vec![
+                DataType::Utf8,
+                DataType::Utf8,
+                DataType::Int64,
+                DataType::Int64,
+                DataType::Utf8,
+            ],
+            Volatility::Immutable,
+        ),
+    })
+}
+
+pub(crate) fn regexp_count() -> ScalarUDF {
+    regexp_search("regexp_count")
+}
+
+pub(crate) fn regexp_instr() -> ScalarUDF {
+    regexp_search("regexp_instr")
+}
+
+fn regexp_search_impl(name: &str, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    let is_scalar = args.iter().all(|a| matches!(a, ColumnarValue::Scalar(_)));
+    let arrays = ColumnarValue::values_to_arrays(args)?;
+    let strings = as_string_array(&arrays[0])?;
+    let patterns = as_string_array(&arrays[1])?;
+    let starts = as_int64_array(&arrays[2])?;
+    let occurrences = as_int64_array(&arrays[3])?;
+    let flags = as_string_array(&arrays[4])?;
+
+    let mut compiled: HashMap<(String, String), Regex> = HashMap::new();
+
+    let result = (0..strings.len())
+        .map(|i| {
+            if strings.is_null(i)
+                || patterns.is_null(i)
+                || starts.is_null(i)
+                || occurrences.is_null(i)
+            {
+                return Ok(None);
+            }
+            let pattern = patterns.value(i);
+            if pattern.is_empty() {
+                return exec_err!("{name} pattern must not be empty");
+            }
+            let start = starts.value(i);
+            if start < 1 {
+                return exec_err!("{name} start must be at least 1, got {start}");
+            }
+            let occurrence = occurrences.value(i);
+            if occurrence < 1 {
+                return exec_err!("{name} occurrence must be at least 1, got {occurrence}");
+            }
+            let flags = if flags.is_null(i) { "" } else { flags.value(i) };
+            if flags.contains('g') {
+                return exec_err!("{name} does not support the 'g' flag");
+            }
+            let regex = cached_regex(&mut compiled, pattern, flags.to_string())?;
+
+            let value = strings.value(i);
+            let offset = char_offset(value, start as usize);
+            let mut matches = regex.find_iter(&value[offset..]);
+            if name == "regexp_count" {
+                return Ok(Some(matches.count() as i64));
+            }
+            Ok(Some(match matches.nth(occurrence as usize - 1) {
+                Some(m) => value[..offset + m.start()].chars().count() as i64 + 1,
+                None => 0,
+            }))
+        })
+        .collect::<Result<Int64Array>>()?;
+
+    if is_scalar {
+        ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+    } else {
+        Ok(ColumnarValue::Array(Arc::new(result)))
+    }
+}
@@ -0,0 +1,121 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `seeded_random(seed, columns...)` returns a number in [0, 1) derived from the
+//! hash of the values of each row, so that the same seed and values always give the
+//! same number, whatever the partitioning of the data. Identical rows get the same
+//! number.
+//!
+//! The hash is FNV-1a over the displayed values followed by the SplitMix64
+//! finalizer, both fixed algorithms, so the numbers do not depend on the machine or
+//! on the versions of the hashing crates.
+
+use std::any::Any;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, Float64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::util::display::{ArrayFormatter, FormatOptions};
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |hash, &b| (hash ^ b as u64).wrapping_mul(FNV_PRIME))
+}
+
+fn splitmix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[derive(Debug)]
+struct SeededRandom {
+    seed: u64,
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for SeededRandom {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "seeded_random"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = args.iter().all(|a| matches!(a, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let len = arrays.first().map_or(1, |a| a.len());
+
+        let options = FormatOptions::default();
+        let formatters = arrays
+            .iter()
+            .map(|array| ArrayFormatter::try_new(array.as_ref(), &options))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let mut hashes = vec![fnv1a(FNV_OFFSET_BASIS, &self.seed.to_le_bytes()); len];
+        let mut value = String::new();
+        for (array, formatter) in arrays.iter().zip(&formatters) {
+            for (i, hash) in hashes.iter_mut().enumerate() {
+                if array.is_null(i) {
+                    *hash = fnv1a(*hash, &[0]);
+                    continue;
+                }
+                value.clear();
+                write!(value, "{}", formatter.value(i)).expect("writing to a String");
+                // the length prefix keeps the values of adjacent columns apart
+                *hash = fnv1a(*hash, &[1]);
+                *hash = fnv1a(*hash, &(value.len() as u64).to_le_bytes());
+                *hash = fnv1a(*hash, value.as_bytes());
+            }
+        }
+        // the top 53 bits of the hash fill the mantissa of a double in [0, 1)
+        let result: ArrayRef = Arc::new(
+            hashes
+                .into_iter()
+                .map(|h| (splitmix64(h) >> 11) as f64 / (1_u64 << 53) as f64)
+                .collect::<Float64Array>(),
+        );
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+pub(crate) fn seeded_random(seed: u64) -> ScalarUDF {
+    ScalarUDF::new_from_impl(SeededRandom {
+        seed,
+        signature: Signature::variadic_any(Volatility::Immutable),
+    })
+}
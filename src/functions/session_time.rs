@@ -0,0 +1,149 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `now`, `current_date` and `current_time` in the session time zone.
+//!
+//! The builtin functions always work in UTC. These read the time zone configured
+//! as `datafusion.execution.time_zone` on the session each time a query is planned,
+//! so `current_date()` is the calendar day of that zone. Like the builtins they are
+//! replaced by the value at the start of the query.
+
+use std::any::Any;
+use std::str::FromStr;
+use std::sync::Weak;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Timelike, Utc};
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::execution::context::SessionState;
+use datafusion_common::{exec_err, Result, ScalarValue};
+use datafusion_expr::simplify::{ExprSimplifyResult, SimplifyInfo};
+use datafusion_expr::{ColumnarValue, Expr, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+use parking_lot::RwLock;
+
+const UTC: &str = "+00:00";
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Now,
+    CurrentDate,
+    CurrentTime,
+}
+
+struct SessionTime {
+    kind: Kind,
+    state: Weak<RwLock<SessionState>>,
+    signature: Signature,
+}
+
+impl std::fmt::Debug for SessionTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SessionTime")
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+impl SessionTime {
+    /// The time zone configured on the session, UTC if there is none
+    fn time_zone(&self) -> String {
+        self.state
+            .upgrade()
+            .and_then(|state| state.read().config().options().execution.time_zone.clone())
+            .unwrap_or_else(|| UTC.to_string())
+    }
+
+    fn value(&self, time: DateTime<Utc>) -> Result<ScalarValue> {
+        let time_zone = self.time_zone();
+        let tz = match Tz::from_str(&time_zone) {
+            Ok(tz) => tz,
+            Err(e) => return exec_err!("Invalid session time zone '{time_zone}': {e}"),
+        };
+        let local = tz.from_utc_datetime(&time.naive_utc());
+        Ok(match self.kind {
+            Kind::Now => {
+                ScalarValue::TimestampNanosecond(time.timestamp_nanos_opt(), Some(time_zone.into()))
+            }
+            Kind::CurrentDate => {
+                let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+                let days = local.date_naive().signed_duration_since(epoch).num_days();
+                ScalarValue::Date32(Some(days as i32))
+            }
+            Kind::CurrentTime => {
+                let nanos = local.num_seconds_from_midnight() as i64 * 1_000_000_000
+                    + local.nanosecond() as i64;
+                ScalarValue::Time64Nanosecond(Some(nanos))
+            }
+        })
+    }
+}
+
+impl ScalarUDFImpl for SessionTime {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        match self.kind {
+            Kind::Now => "now",
+            Kind::CurrentDate => "current_date",
+            Kind::CurrentTime => "current_time",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(match self.kind {
+            Kind::Now => DataType::Timestamp(TimeUnit::Nanosecond, Some(self.time_zone().into())),
+            Kind::CurrentDate => DataType::Date32,
+            Kind::CurrentTime => DataType::Time64(TimeUnit::Nanosecond),
+        })
+    }
+
+    fn invoke(&self, _args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        // only reached when the expression was not simplified
+        Ok(ColumnarValue::Scalar(self.value(Utc::now())?))
+    }
+
+    fn simplify(&self, _args: Vec<Expr>, info: &dyn SimplifyInfo) -> Result<ExprSimplifyResult> {
+        let start = info.execution_props().query_execution_start_time;
+        Ok(ExprSimplifyResult::Simplified(Expr::Literal(
+            self.value(start)?,
+        )))
+    }
+}
+
+fn session_time(kind: Kind, state: Weak<RwLock<SessionState>>) -> ScalarUDF {
+    ScalarUDF::new_from_impl(SessionTime {
+        kind,
+        state,
+        signature: Signature::exact(vec![], Volatility::Stable),
+    })
+}
+
+/// `now`, `current_date` and `current_time` following the time zone of the session
+/// behind `state`
+pub(crate) fn session_time_functions(state: Weak<RwLock<SessionState>>) -> Vec<ScalarUDF> {
+    vec![
+        session_time(Kind::Now, state.clone()),
+        session_time(Kind::CurrentDate, state.clone()),
+        session_time(Kind::CurrentTime, state),
+    ]
+}
@@ -0,0 +1,101 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `to_json(expr)` encodes each value as a JSON string, with nested structs as
+//! objects, lists as arrays and NULL values inside them as `null`. A NULL value
+//! itself gives NULL rather than the string `null`.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, StringBuilder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::json::writer::{LineDelimited, WriterBuilder};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion_common::{exec_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+
+/// Each row is written as the object `{"v":<value>}`, the value is what lies between
+/// this prefix and the closing brace
+const PREFIX: &str = "{\"v\":";
+
+#[derive(Debug)]
+struct ToJson {
+    signature: Signature,
+}
+
+impl ScalarUDFImpl for ToJson {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "to_json"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = matches!(args[0], ColumnarValue::Scalar(_));
+        let array = ColumnarValue::values_to_arrays(args)?.remove(0);
+
+        let field = Field::new("v", array.data_type().clone(), true);
+        let batch = RecordBatch::try_new(Arc::new(Schema::new(vec![field])), vec![array.clone()])?;
+        let mut writer = WriterBuilder::new()
+            .with_explicit_nulls(true)
+            .build::<_, LineDelimited>(vec![]);
+        writer.write(&batch)?;
+        writer.finish()?;
+        let buf = writer.into_inner();
+        let lines = std::str::from_utf8(&buf)
+            .map_err(|e| DataFusionError::Execution(format!("Invalid JSON output: {e}")))?;
+
+        let mut builder = StringBuilder::with_capacity(array.len(), buf.len());
+        for (i, line) in lines.lines().enumerate() {
+            if array.is_null(i) {
+                builder.append_null();
+                continue;
+            }
+            match line
+                .strip_prefix(PREFIX)
+                .and_then(|rest| rest.strip_suffix('}'))
+            {
+                Some(value) => builder.append_value(value),
+                None => return exec_err!("Unexpected JSON encoding of row {i}: {line}"),
+            }
+        }
+        let result = Arc::new(builder.finish()) as _;
+
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+pub(crate) fn to_json() -> ScalarUDF {
+    ScalarUDF::new_from_impl(ToJson {
+        signature: Signature::any(1, Volatility::Immutable),
+    })
+}
@@ -0,0 +1,100 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `to_timestamp` with an explicit unit for integer input.
+//!
+//! Integers are reinterpreted as a timestamp of the requested unit without any
+//! scaling, so the result keeps that unit's precision and large values cannot
+//! overflow. Strings carry their own precision and are parsed like the builtin
+//! `to_timestamp`, whatever the unit.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::functions::datetime;
+use datafusion_common::Result;
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
+
+#[derive(Debug)]
+struct ToTimestampWithUnit {
+    unit: TimeUnit,
+    signature: Signature,
+}
+
+impl ToTimestampWithUnit {
+    /// The builtin function handling input other than integers
+    fn fallback(&self, arg_type: &DataType) -> Arc<ScalarUDF> {
+        match (arg_type, &self.unit) {
+            (DataType::Utf8 | DataType::LargeUtf8, _) => datetime::to_timestamp(),
+            (_, TimeUnit::Second) => datetime::to_timestamp_seconds(),
+            (_, TimeUnit::Millisecond) => datetime::to_timestamp_millis(),
+            (_, TimeUnit::Microsecond) => datetime::to_timestamp_micros(),
+            (_, TimeUnit::Nanosecond) => datetime::to_timestamp_nanos(),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToTimestampWithUnit {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "to_timestamp"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if arg_types[0].is_integer() {
+            Ok(DataType::Timestamp(self.unit.clone(), None))
+        } else {
+            self.fallback(&arg_types[0]).inner().return_type(arg_types)
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let arg_type = args[0].data_type();
+        if !arg_type.is_integer() {
+            return self.fallback(&arg_type).invoke(args);
+        }
+
+        let timestamp_type = DataType::Timestamp(self.unit.clone(), None);
+        match &args[0] {
+            ColumnarValue::Array(array) => {
+                let array = cast(&cast(array, &DataType::Int64)?, &timestamp_type)?;
+                Ok(ColumnarValue::Array(array))
+            }
+            ColumnarValue::Scalar(scalar) => scalar
+                .cast_to(&DataType::Int64)
+                .and_then(|value| value.cast_to(&timestamp_type))
+                .map(ColumnarValue::Scalar),
+        }
+    }
+}
+
+/// Returns a `to_timestamp` interpreting integers in the given unit
+pub(crate) fn to_timestamp_with_unit(unit: TimeUnit) -> ScalarUDF {
+    ScalarUDF::new_from_impl(ToTimestampWithUnit {
+        unit,
+        signature: Signature::variadic_any(Volatility::Immutable),
+    })
+}
@@ -0,0 +1,129 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `zip_with(left, right, func)` applies a scalar function to the elements of two
+//! lists pairwise. The shorter list is padded with NULLs, and the element type of
+//! the result is the return type of the function.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, ListArray, UInt64Array};
+use datafusion::arrow::buffer::{NullBuffer, OffsetBuffer};
+use datafusion::arrow::compute::{cast, take};
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion_common::cast::as_list_array;
+use datafusion_common::{plan_err, Result, ScalarValue};
+use datafusion_expr::type_coercion::functions::data_types;
+use datafusion_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature};
+
+#[derive(Debug)]
+struct ZipWith {
+    func: Arc<ScalarUDF>,
+    signature: Signature,
+}
+
+impl ZipWith {
+    /// Types the elements are cast to before calling the function
+    fn element_types(&self, left: &DataType, right: &DataType) -> Result<Vec<DataType>> {
+        data_types(&[left.clone(), right.clone()], self.func.signature())
+    }
+}
+
+impl ScalarUDFImpl for ZipWith {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "zip_with"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match (&arg_types[0], &arg_types[1]) {
+            (DataType::List(left), DataType::List(right)) => {
+                let element_types = self.element_types(left.data_type(), right.data_type())?;
+                let element_type = self.func.inner().return_type(&element_types)?;
+                Ok(DataType::new_list(element_type, true))
+            }
+            (left, right) => plan_err!("zip_with expects two lists, got {left} and {right}"),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let is_scalar = args.iter().all(|a| matches!(a, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(args)?;
+        let left = as_list_array(&arrays[0])?;
+        let right = as_list_array(&arrays[1])?;
+
+        // flatten both sides into aligned element arrays, padding the shorter list of
+        // each row with NULLs
+        let mut left_indices = vec![];
+        let mut right_indices = vec![];
+        let mut offsets = vec![0_i32];
+        let mut validity = vec![];
+        for i in 0..left.len() {
+            let valid = left.is_valid(i) && right.is_valid(i);
+            if valid {
+                let (left_start, left_end) = (left.offsets()[i], left.offsets()[i + 1]);
+                let (right_start, right_end) = (right.offsets()[i], right.offsets()[i + 1]);
+                let len = (left_end - left_start).max(right_end - right_start);
+                for j in 0..len {
+                    left_indices.push((left_start + j < left_end).then(|| (left_start + j) as u64));
+                    right_indices
+                        .push((right_start + j < right_end).then(|| (right_start + j) as u64));
+                }
+            }
+            offsets.push(left_indices.len() as i32);
+            validity.push(valid);
+        }
+
+        let element_types = self.element_types(&left.value_type(), &right.value_type())?;
+        let left_values = take(left.values(), &UInt64Array::from(left_indices), None)?;
+        let right_values = take(right.values(), &UInt64Array::from(right_indices), None)?;
+        let values = self
+            .func
+            .invoke(&[
+                ColumnarValue::Array(cast(&left_values, &element_types[0])?),
+                ColumnarValue::Array(cast(&right_values, &element_types[1])?),
+            ])?
+            .into_array(left_values.len())?;
+
+        let result: ArrayRef = Arc::new(ListArray::try_new(
+            Arc::new(Field::new("item", values.data_type().clone(), true)),
+            OffsetBuffer::new(offsets.into()),
+            values,
+            Some(NullBuffer::from(validity)),
+        )?);
+        if is_scalar {
+            ScalarValue::try_from_array(&result, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+pub(crate) fn zip_with(func: Arc<ScalarUDF>) -> ScalarUDF {
+    ScalarUDF::new_from_impl(ZipWith {
+        signature: Signature::any(2, func.signature().volatility),
+        func,
+    })
+}
@@ -24,11 +24,13 @@ pub use datafusion;
 pub use datafusion_common;
 pub use datafusion_expr;
 pub use datafusion_optimizer;
+pub use datafusion_proto;
 pub use datafusion_sql;
 
 #[cfg(feature = "substrait")]
 pub use datafusion_substrait;
 
+mod broadcast_join;
 #[allow(clippy::borrow_deref_ref)]
 pub mod catalog;
 pub mod common;
@@ -44,12 +46,16 @@ pub mod errors;
 #[allow(clippy::borrow_deref_ref)]
 pub mod expr;
 #[allow(clippy::borrow_deref_ref)]
+mod file_format;
 mod functions;
+mod memory_pool;
+mod optimizer_rule;
 pub mod physical_plan;
 mod pyarrow_filter_expression;
 mod record_batch;
 pub mod sql;
 pub mod store;
+mod table_provider;
 
 #[cfg(feature = "substrait")]
 pub mod substrait;
@@ -57,6 +63,8 @@ pub mod substrait;
 mod udaf;
 #[allow(clippy::borrow_deref_ref)]
 mod udf;
+mod udtf;
+mod udwf;
 pub mod utils;
 
 #[cfg(feature = "mimalloc")]
@@ -89,9 +97,11 @@ fn _internal(py: Python, m: Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<dataframe::PyDataFrame>()?;
     m.add_class::<udf::PyScalarUDF>()?;
     m.add_class::<udaf::PyAggregateUDF>()?;
+    m.add_class::<udwf::PyWindowUDF>()?;
     m.add_class::<config::PyConfig>()?;
     m.add_class::<sql::logical::PyLogicalPlan>()?;
     m.add_class::<physical_plan::PyExecutionPlan>()?;
+    m.add_wrapped(wrap_pyfunction!(dataframe::plan_diff))?;
 
     // Register `common` as a submodule. Matching `datafusion-common` https://docs.rs/datafusion-common/latest/datafusion_common/
     let common = PyModule::new_bound(py, "common")?;
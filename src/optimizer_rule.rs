@@ -0,0 +1,115 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Bridges optimizer and analyzer rules written in Python into the DataFusion planner
+
+use pyo3::prelude::*;
+
+use datafusion::common::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::optimizer::analyzer::AnalyzerRule;
+use datafusion::optimizer::{OptimizerConfig, OptimizerRule};
+use datafusion_expr::LogicalPlan;
+
+use crate::sql::logical::PyLogicalPlan;
+
+fn py_err(err: PyErr) -> DataFusionError {
+    DataFusionError::External(Box::new(err))
+}
+
+/// The name of a Python rule: the result of its `name()` method if it has one,
+/// otherwise its class name
+fn rule_name(rule: &Bound<'_, PyAny>) -> PyResult<String> {
+    if rule.hasattr("name")? {
+        rule.call_method0("name")?.extract()
+    } else {
+        Ok(rule.get_type().name()?.to_string())
+    }
+}
+
+/// Calls `method` of the Python rule with `plan`, returning the rewritten plan or
+/// `None` when the rule left the plan unchanged
+fn call_rule(rule: &PyObject, method: &str, plan: &LogicalPlan) -> DFResult<Option<LogicalPlan>> {
+    Python::with_gil(|py| {
+        let result = rule
+            .bind(py)
+            .call_method1(method, (PyLogicalPlan::new(plan.clone()),))
+            .map_err(py_err)?;
+        if result.is_none() {
+            return Ok(None);
+        }
+        let plan: PyLogicalPlan = result.extract().map_err(py_err)?;
+        Ok(Some(plan.plan().as_ref().clone()))
+    })
+}
+
+/// An `OptimizerRule` delegating to a Python object implementing
+/// `try_optimize(plan)`, which returns a rewritten `LogicalPlan` or None.
+/// The rule is applied once to the root of the plan in every optimizer pass.
+pub(crate) struct PyOptimizerRule {
+    rule: PyObject,
+    name: String,
+}
+
+impl PyOptimizerRule {
+    pub fn new(rule: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            rule: rule.clone().unbind(),
+            name: rule_name(rule)?,
+        })
+    }
+}
+
+impl OptimizerRule for PyOptimizerRule {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> DFResult<Option<LogicalPlan>> {
+        call_rule(&self.rule, "try_optimize", plan)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// An `AnalyzerRule` delegating to a Python object implementing `analyze(plan)`,
+/// which returns a rewritten `LogicalPlan` or None to keep the plan
+pub(crate) struct PyAnalyzerRule {
+    rule: PyObject,
+    name: String,
+}
+
+impl PyAnalyzerRule {
+    pub fn new(rule: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            rule: rule.clone().unbind(),
+            name: rule_name(rule)?,
+        })
+    }
+}
+
+impl AnalyzerRule for PyAnalyzerRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> DFResult<LogicalPlan> {
+        Ok(call_rule(&self.rule, "analyze", &plan)?.unwrap_or(plan))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
@@ -16,9 +16,13 @@
 // under the License.
 
 use datafusion::physical_plan::{displayable, ExecutionPlan, ExecutionPlanProperties};
+use datafusion_proto::bytes::{physical_plan_from_bytes, physical_plan_to_bytes};
 use std::sync::Arc;
 
-use pyo3::prelude::*;
+use pyo3::{prelude::*, types::PyBytes};
+
+use crate::context::PySessionContext;
+use crate::errors::DataFusionError;
 
 #[pyclass(name = "ExecutionPlan", module = "datafusion", subclass)]
 #[derive(Debug, Clone)]
@@ -44,6 +48,11 @@ impl PyExecutionPlan {
             .collect()
     }
 
+    /// Get the inputs to this plan, the same as `children`
+    pub fn inputs(&self) -> Vec<PyExecutionPlan> {
+        self.children()
+    }
+
     pub fn display(&self) -> String {
         let d = displayable(self.plan.as_ref());
         format!("{}", d.one_line())
@@ -54,6 +63,21 @@ impl PyExecutionPlan {
         format!("{}", d.indent(false))
     }
 
+    /// Serialize this plan to protobuf bytes
+    pub fn to_proto(&self, py: Python) -> PyResult<PyObject> {
+        let proto_bytes =
+            physical_plan_to_bytes(self.plan.clone()).map_err(DataFusionError::from)?;
+        Ok(PyBytes::new_bound(py, &proto_bytes).unbind().into())
+    }
+
+    /// Deserialize protobuf bytes to a plan. Any functions referenced by the plan
+    /// are resolved against the function registry of `ctx`.
+    #[staticmethod]
+    pub fn from_proto(proto_msg: Vec<u8>, ctx: &PySessionContext) -> PyResult<Self> {
+        let plan = physical_plan_from_bytes(&proto_msg, &ctx.ctx).map_err(DataFusionError::from)?;
+        Ok(Self::new(plan))
+    }
+
     fn __repr__(&self) -> String {
         self.display_indent()
     }
@@ -62,6 +86,12 @@ impl PyExecutionPlan {
     pub fn partition_count(&self) -> usize {
         self.plan.output_partitioning().partition_count()
     }
+
+    /// How the output of this plan is partitioned, e.g. `Hash([a@0], 4)`
+    #[getter]
+    pub fn output_partitioning(&self) -> String {
+        format!("{}", self.plan.output_partitioning())
+    }
 }
 
 impl From<PyExecutionPlan> for Arc<dyn ExecutionPlan> {
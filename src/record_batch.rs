@@ -15,7 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::utils::wait_for_future;
+use crate::utils::wait_for_future_interruptible;
 use datafusion::arrow::pyarrow::ToPyArrow;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::physical_plan::SendableRecordBatchStream;
@@ -56,7 +56,7 @@ impl PyRecordBatchStream {
 impl PyRecordBatchStream {
     fn next(&mut self, py: Python) -> PyResult<Option<PyRecordBatch>> {
         let result = self.stream.next();
-        match wait_for_future(py, result) {
+        match wait_for_future_interruptible(py, result)? {
             None => Ok(None),
             Some(Ok(b)) => Ok(Some(b.into())),
             Some(Err(e)) => Err(e.into()),
@@ -17,7 +17,8 @@
 
 use std::sync::Arc;
 
-use crate::errors::py_unsupported_variant_err;
+use crate::context::PySessionContext;
+use crate::errors::{py_unsupported_variant_err, DataFusionError};
 use crate::expr::aggregate::PyAggregate;
 use crate::expr::analyze::PyAnalyze;
 use crate::expr::cross_join::PyCrossJoin;
@@ -36,7 +37,8 @@ use crate::expr::table_scan::PyTableScan;
 use crate::expr::unnest::PyUnnest;
 use crate::expr::window::PyWindow;
 use datafusion_expr::LogicalPlan;
-use pyo3::prelude::*;
+use datafusion_proto::bytes::{logical_plan_from_bytes, logical_plan_to_bytes};
+use pyo3::{prelude::*, types::PyBytes};
 
 use crate::expr::logical_node::LogicalNode;
 
@@ -116,6 +118,20 @@ impl PyLogicalPlan {
     fn display_graphviz(&self) -> String {
         format!("{}", self.plan.display_graphviz())
     }
+
+    /// Serialize this plan to protobuf bytes
+    pub fn to_proto(&self, py: Python) -> PyResult<PyObject> {
+        let proto_bytes = logical_plan_to_bytes(&self.plan).map_err(DataFusionError::from)?;
+        Ok(PyBytes::new_bound(py, &proto_bytes).unbind().into())
+    }
+
+    /// Deserialize protobuf bytes to a plan. Any functions referenced by the plan
+    /// are resolved against the function registry of `ctx`.
+    #[staticmethod]
+    pub fn from_proto(proto_msg: Vec<u8>, ctx: &PySessionContext) -> PyResult<Self> {
+        let plan = logical_plan_from_bytes(&proto_msg, &ctx.ctx).map_err(DataFusionError::from)?;
+        Ok(Self::new(plan))
+    }
 }
 
 impl From<PyLogicalPlan> for LogicalPlan {
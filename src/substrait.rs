@@ -35,7 +35,7 @@ pub struct PyPlan {
 
 #[pymethods]
 impl PyPlan {
-    fn encode(&self, py: Python) -> PyResult<PyObject> {
+    pub fn encode(&self, py: Python) -> PyResult<PyObject> {
         let mut proto_bytes = Vec::<u8>::new();
         self.plan
             .encode(&mut proto_bytes)
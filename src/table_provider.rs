@@ -0,0 +1,289 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+/// Implements a Datafusion TableProvider that delegates to a Python object
+/// This allows custom data sources to be written in Python while still receiving
+/// the projection, filters and limit of the scan
+use pyo3::prelude::*;
+use pyo3::types::{PyIterator, PyList};
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::{stream, TryStreamExt};
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::pyarrow::PyArrowType;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::logical_expr::TableProviderFilterPushDown;
+use datafusion::physical_expr::EquivalenceProperties;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionMode, ExecutionPlan, Partitioning, PlanProperties,
+    SendableRecordBatchStream,
+};
+use datafusion_expr::Expr;
+
+use crate::dataset_exec::PyArrowBatchesAdapter;
+use crate::expr::PyExpr;
+
+fn py_err(err: PyErr) -> DataFusionError {
+    DataFusionError::External(Box::new(err))
+}
+
+/// Wraps a Python object implementing `schema()` and
+/// `scan(projection, filters, limit)`, and optionally
+/// `supports_filters_pushdown(filters)`.
+#[derive(Debug)]
+pub(crate) struct PyTableProvider {
+    provider: PyObject,
+    schema: SchemaRef,
+}
+
+impl PyTableProvider {
+    pub fn new(provider: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let schema = provider
+            .call_method0("schema")?
+            .extract::<PyArrowType<_>>()?
+            .0;
+        Ok(Self {
+            provider: provider.clone().unbind(),
+            schema: Arc::new(schema),
+        })
+    }
+
+    /// Asks the provider which filters it applies, filters it cannot handle (or all of them
+    /// when `supports_filters_pushdown` is not implemented) are `Unsupported` so DataFusion
+    /// applies them after the scan
+    fn filter_pushdowns(&self, filters: &[&Expr]) -> DFResult<Vec<TableProviderFilterPushDown>> {
+        Python::with_gil(|py| {
+            let provider = self.provider.bind(py);
+            if !provider
+                .hasattr("supports_filters_pushdown")
+                .map_err(py_err)?
+            {
+                return Ok(vec![
+                    TableProviderFilterPushDown::Unsupported;
+                    filters.len()
+                ]);
+            }
+
+            let py_filters = filters
+                .iter()
+                .map(|&f| PyExpr::from(f.clone()).into_py(py))
+                .collect::<Vec<_>>();
+            let support: Vec<String> = provider
+                .call_method1("supports_filters_pushdown", (py_filters,))
+                .and_then(|support| support.extract())
+                .map_err(py_err)?;
+            if support.len() != filters.len() {
+                return Err(DataFusionError::Execution(format!(
+                    "supports_filters_pushdown returned {} values for {} filters",
+                    support.len(),
+                    filters.len()
+                )));
+            }
+
+            support
+                .iter()
+                .map(|s| match s.as_str() {
+                    "exact" => Ok(TableProviderFilterPushDown::Exact),
+                    "inexact" => Ok(TableProviderFilterPushDown::Inexact),
+                    "unsupported" => Ok(TableProviderFilterPushDown::Unsupported),
+                    other => Err(DataFusionError::Execution(format!(
+                        "Unknown filter pushdown '{other}', expected exact, inexact or unsupported"
+                    ))),
+                })
+                .collect()
+        })
+    }
+}
+
+#[async_trait]
+impl TableProvider for PyTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _ctx: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let schema = match projection {
+            Some(projection) => Arc::new(self.schema.project(projection)?),
+            None => self.schema.clone(),
+        };
+        // ask again rather than remembering the answers given while planning, which may
+        // have been for another query
+        let exact_filters = self
+            .filter_pushdowns(&filters.iter().collect::<Vec<_>>())?
+            .into_iter()
+            .map(|pushdown| pushdown == TableProviderFilterPushDown::Exact)
+            .collect();
+        let plan = Python::with_gil(|py| PyTableProviderExec {
+            provider: self.provider.clone_ref(py),
+            projection: projection.cloned(),
+            filters: filters.to_vec(),
+            exact_filters,
+            limit,
+            plan_properties: PlanProperties::new(
+                EquivalenceProperties::new(schema.clone()),
+                Partitioning::UnknownPartitioning(1),
+                ExecutionMode::Bounded,
+            ),
+            schema,
+        });
+        Ok(Arc::new(plan))
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> DFResult<Vec<TableProviderFilterPushDown>> {
+        self.filter_pushdowns(filters)
+    }
+}
+
+/// Scans a Python table provider, calling its `scan` method once the plan is executed
+#[derive(Debug)]
+struct PyTableProviderExec {
+    provider: PyObject,
+    schema: SchemaRef,
+    projection: Option<Vec<usize>>,
+    filters: Vec<Expr>,
+    /// Whether each of `filters` is applied exactly by the provider
+    exact_filters: Vec<bool>,
+    limit: Option<usize>,
+    plan_properties: PlanProperties,
+}
+
+impl ExecutionPlan for PyTableProviderExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        // this is a leaf node and has no children
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> DFResult<SendableRecordBatchStream> {
+        Python::with_gil(|py| {
+            let filters = self
+                .filters
+                .iter()
+                .map(|f| PyExpr::from(f.clone()).into_py(py))
+                .collect::<Vec<_>>();
+            let batches = self
+                .provider
+                .bind(py)
+                .call_method1(
+                    "scan",
+                    (
+                        self.projection.clone(),
+                        PyList::new_bound(py, filters),
+                        self.limit,
+                    ),
+                )
+                .map_err(py_err)?;
+            let batches: Bound<'_, PyIterator> =
+                if batches.hasattr("to_batches").map_err(py_err)? {
+                    batches.call_method0("to_batches").map_err(py_err)?.iter()
+                } else {
+                    batches.iter()
+                }
+                .map_err(py_err)?;
+
+            let batches = PyArrowBatchesAdapter {
+                batches: batches.into(),
+            };
+            let stream: SendableRecordBatchStream = Box::pin(RecordBatchStreamAdapter::new(
+                self.schema.clone(),
+                stream::iter(batches).map_err(|e| e.into()),
+            ));
+            Ok(stream)
+        })
+    }
+}
+
+impl DisplayAs for PyTableProviderExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let projected_columns: Vec<String> = self
+                    .schema
+                    .fields()
+                    .iter()
+                    .map(|x| x.name().to_owned())
+                    .collect();
+                let (exact, inexact): (Vec<_>, Vec<_>) = self
+                    .filters
+                    .iter()
+                    .zip(&self.exact_filters)
+                    .partition(|(_, &exact)| exact);
+                let names = |filters: Vec<(&Expr, &bool)>| {
+                    filters
+                        .iter()
+                        .map(|(f, _)| f.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                write!(
+                    f,
+                    "PyTableProviderExec: projection=[{}], exact_filters=[{}], inexact_filters=[{}], limit={:?}",
+                    projected_columns.join(", "),
+                    names(exact),
+                    names(inexact),
+                    self.limit
+                )
+            }
+        }
+    }
+}
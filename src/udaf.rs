@@ -32,23 +32,45 @@ use crate::utils::parse_volatility;
 #[derive(Debug)]
 struct RustAccumulator {
     accum: PyObject,
+    return_type: DataType,
+    state_types: Arc<Vec<DataType>>,
 }
 
 impl RustAccumulator {
-    fn new(accum: PyObject) -> Self {
-        Self { accum }
+    fn new(accum: PyObject, return_type: DataType, state_types: Arc<Vec<DataType>>) -> Self {
+        Self {
+            accum,
+            return_type,
+            state_types,
+        }
     }
 }
 
 impl Accumulator for RustAccumulator {
     fn state(&mut self) -> Result<Vec<ScalarValue>> {
-        Python::with_gil(|py| self.accum.bind(py).call_method0("state")?.extract())
-            .map_err(|e| DataFusionError::Execution(format!("{e}")))
+        let state: Vec<ScalarValue> =
+            Python::with_gil(|py| self.accum.bind(py).call_method0("state")?.extract())
+                .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+        if state.len() != self.state_types.len() {
+            return Err(DataFusionError::Execution(format!(
+                "state returned {} values but {} state types were declared",
+                state.len(),
+                self.state_types.len()
+            )));
+        }
+        // cast each value to its declared type so that partial states can be merged
+        state
+            .iter()
+            .zip(self.state_types.iter())
+            .map(|(value, data_type)| value.cast_to(data_type))
+            .collect()
     }
 
     fn evaluate(&mut self) -> Result<ScalarValue> {
-        Python::with_gil(|py| self.accum.bind(py).call_method0("evaluate")?.extract())
-            .map_err(|e| DataFusionError::Execution(format!("{e}")))
+        let value: ScalarValue =
+            Python::with_gil(|py| self.accum.bind(py).call_method0("evaluate")?.extract())
+                .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+        value.cast_to(&self.return_type)
     }
 
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
@@ -72,18 +94,22 @@ impl Accumulator for RustAccumulator {
 
     fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
         Python::with_gil(|py| {
-            let state = &states[0];
-
-            // 1. cast states to Pyarrow array
-            let state = state
-                .into_data()
-                .to_pyarrow(py)
-                .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+            // 1. cast states to Pyarrow arrays, one per state field
+            let states = states
+                .iter()
+                .map(|state| {
+                    state
+                        .into_data()
+                        .to_pyarrow(py)
+                        .map_err(|e| DataFusionError::Execution(format!("{e}")))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let states = PyTuple::new_bound(py, states);
 
             // 2. call merge
             self.accum
                 .bind(py)
-                .call_method1("merge", (state,))
+                .call_method1("merge", states)
                 .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
 
             Ok(())
@@ -123,14 +149,21 @@ impl Accumulator for RustAccumulator {
     }
 }
 
-pub fn to_rust_accumulator(accum: PyObject) -> AccumulatorFactoryFunction {
-    Arc::new(move |_| -> Result<Box<dyn Accumulator>> {
+pub fn to_rust_accumulator(
+    accum: PyObject,
+    state_types: Arc<Vec<DataType>>,
+) -> AccumulatorFactoryFunction {
+    Arc::new(move |args| -> Result<Box<dyn Accumulator>> {
         let accum = Python::with_gil(|py| {
             accum
                 .call0(py)
                 .map_err(|e| DataFusionError::Execution(format!("{e}")))
         })?;
-        Ok(Box::new(RustAccumulator::new(accum)))
+        Ok(Box::new(RustAccumulator::new(
+            accum,
+            args.data_type.clone(),
+            state_types.clone(),
+        )))
     })
 }
 
@@ -153,13 +186,14 @@ impl PyAggregateUDF {
         state_type: PyArrowType<Vec<DataType>>,
         volatility: &str,
     ) -> PyResult<Self> {
+        let state_type = Arc::new(state_type.0);
         let function = create_udaf(
             name,
             input_type.0,
             Arc::new(return_type.0),
             parse_volatility(volatility)?,
-            to_rust_accumulator(accumulator),
-            Arc::new(state_type.0),
+            to_rust_accumulator(accumulator, state_type.clone()),
+            state_type,
         );
         Ok(Self { function })
     }
@@ -0,0 +1,110 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use pyo3::{prelude::*, types::PyTuple};
+
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::pyarrow::{PyArrowType, ToPyArrow};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::function::TableFunctionImpl;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::error::{DataFusionError, Result};
+use datafusion_expr::Expr;
+
+/// A table function backed by a Python callable. The callable receives the
+/// literal arguments of the call as Python values and returns either a
+/// `pyarrow.Table` or a list of record batches.
+#[derive(Debug)]
+pub(crate) struct PyTableFunction {
+    name: String,
+    func: PyObject,
+}
+
+impl PyTableFunction {
+    pub fn new(name: &str, func: PyObject) -> Self {
+        Self {
+            name: name.to_owned(),
+            func,
+        }
+    }
+}
+
+impl TableFunctionImpl for PyTableFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        Python::with_gil(|py| {
+            // 1. convert the literal arguments to Python values
+            let py_args = args
+                .iter()
+                .map(|arg| match arg {
+                    Expr::Literal(value) => value
+                        .to_pyarrow(py)
+                        .and_then(|value| value.call_method0(py, "as_py"))
+                        .map_err(|e| DataFusionError::Execution(format!("{e}"))),
+                    _ => Err(DataFusionError::Plan(format!(
+                        "Arguments of table function {} must be literals, got {arg}",
+                        self.name
+                    ))),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let py_args = PyTuple::new_bound(py, py_args);
+
+            // 2. check the arguments match the signature of the callable
+            let func = self.func.bind(py);
+            let signature = py
+                .import_bound("inspect")
+                .and_then(|inspect| inspect.call_method1("signature", (func,)))
+                .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+            signature
+                .call_method1("bind", py_args.clone())
+                .map_err(|e| {
+                    DataFusionError::Plan(format!(
+                        "Invalid arguments for table function {}: {}",
+                        self.name,
+                        e.value_bound(py)
+                    ))
+                })?;
+
+            // 3. call function
+            let mut value = func
+                .call1(py_args)
+                .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+
+            // 4. collect the result into an in-memory table
+            if !value.hasattr("to_batches").unwrap_or(false) {
+                value = py
+                    .import_bound("pyarrow")
+                    .and_then(|pa| pa.getattr("Table"))
+                    .and_then(|table| table.call_method1("from_batches", (value,)))
+                    .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+            }
+            let schema: PyArrowType<Schema> = value
+                .getattr("schema")
+                .and_then(|schema| schema.extract())
+                .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+            let batches: Vec<PyArrowType<RecordBatch>> = value
+                .call_method0("to_batches")
+                .and_then(|batches| batches.extract())
+                .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+            let batches = batches.into_iter().map(|batch| batch.0).collect();
+
+            let table = MemTable::try_new(Arc::new(schema.0), vec![batches])?;
+            Ok(Arc::new(table) as Arc<dyn TableProvider>)
+        })
+    }
+}
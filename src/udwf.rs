@@ -0,0 +1,192 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use pyo3::{prelude::*, types::PyTuple};
+
+use datafusion::arrow::array::{make_array, Array, ArrayData, ArrayRef};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::pyarrow::{FromPyArrow, PyArrowType, ToPyArrow};
+use datafusion::error::{DataFusionError, Result};
+use datafusion_expr::{
+    PartitionEvaluator, PartitionEvaluatorFactory, Signature, Volatility, WindowFrame, WindowUDF,
+    WindowUDFImpl,
+};
+
+use crate::expr::PyExpr;
+use crate::utils::parse_volatility;
+
+#[derive(Debug)]
+struct RustPartitionEvaluator {
+    evaluator: PyObject,
+}
+
+impl RustPartitionEvaluator {
+    fn new(evaluator: PyObject) -> Self {
+        Self { evaluator }
+    }
+}
+
+impl PartitionEvaluator for RustPartitionEvaluator {
+    fn evaluate_all(&mut self, values: &[ArrayRef], num_rows: usize) -> Result<ArrayRef> {
+        Python::with_gil(|py| {
+            // 1. cast the ordered partition values to Pyarrow arrays
+            let py_args = values
+                .iter()
+                .map(|arg| {
+                    arg.into_data()
+                        .to_pyarrow(py)
+                        .map_err(|e| DataFusionError::Execution(format!("{e}")))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let py_args = PyTuple::new_bound(py, py_args);
+
+            // 2. call evaluate_all
+            let value = self
+                .evaluator
+                .bind(py)
+                .call_method1("evaluate_all", (py_args, num_rows))
+                .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+
+            // 3. cast to arrow::array::Array, one value per input row
+            let array_data = ArrayData::from_pyarrow_bound(&value)
+                .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+            if array_data.len() != num_rows {
+                return Err(DataFusionError::Execution(format!(
+                    "evaluate_all returned {} values for a partition of {num_rows} rows",
+                    array_data.len()
+                )));
+            }
+            Ok(make_array(array_data))
+        })
+    }
+}
+
+pub fn to_rust_partition_evaluator(evaluator: PyObject) -> PartitionEvaluatorFactory {
+    Arc::new(move || -> Result<Box<dyn PartitionEvaluator>> {
+        let evaluator = Python::with_gil(|py| {
+            evaluator
+                .call0(py)
+                .map_err(|e| DataFusionError::Execution(format!("{e}")))
+        })?;
+        Ok(Box::new(RustPartitionEvaluator::new(evaluator)))
+    })
+}
+
+/// A window function taking any number of input columns, backed by a
+/// Python partition evaluator
+struct MultiColumnWindowUDF {
+    name: String,
+    signature: Signature,
+    return_type: DataType,
+    partition_evaluator_factory: PartitionEvaluatorFactory,
+}
+
+impl std::fmt::Debug for MultiColumnWindowUDF {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("WindowUDF")
+            .field("name", &self.name)
+            .field("signature", &self.signature)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+impl MultiColumnWindowUDF {
+    fn new(
+        name: impl Into<String>,
+        input_types: Vec<DataType>,
+        return_type: DataType,
+        volatility: Volatility,
+        partition_evaluator_factory: PartitionEvaluatorFactory,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            signature: Signature::exact(input_types, volatility),
+            return_type,
+            partition_evaluator_factory,
+        }
+    }
+}
+
+impl WindowUDFImpl for MultiColumnWindowUDF {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(self.return_type.clone())
+    }
+
+    fn partition_evaluator(&self) -> Result<Box<dyn PartitionEvaluator>> {
+        (self.partition_evaluator_factory)()
+    }
+}
+
+/// Represents a WindowUDF
+#[pyclass(name = "WindowUDF", module = "datafusion", subclass)]
+#[derive(Debug, Clone)]
+pub struct PyWindowUDF {
+    pub(crate) function: WindowUDF,
+}
+
+#[pymethods]
+impl PyWindowUDF {
+    #[new]
+    #[pyo3(signature=(name, evaluator, input_types, return_type, volatility))]
+    fn new(
+        name: &str,
+        evaluator: PyObject,
+        input_types: PyArrowType<Vec<DataType>>,
+        return_type: PyArrowType<DataType>,
+        volatility: &str,
+    ) -> PyResult<Self> {
+        let function = WindowUDF::new_from_impl(MultiColumnWindowUDF::new(
+            name,
+            input_types.0,
+            return_type.0,
+            parse_volatility(volatility)?,
+            to_rust_partition_evaluator(evaluator),
+        ));
+        Ok(Self { function })
+    }
+
+    /// creates a new PyExpr with the call of the udwf over the whole input, as one
+    /// partition without ordering. `functions.window` adds partitions and ordering
+    #[pyo3(signature = (*args))]
+    fn __call__(&self, args: Vec<PyExpr>) -> PyResult<PyExpr> {
+        let args = args.iter().map(|e| e.expr.clone()).collect();
+        Ok(self
+            .function
+            .call(args, vec![], vec![], WindowFrame::new(None))
+            .into())
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("WindowUDF({})", self.function.name()))
+    }
+}
@@ -17,11 +17,33 @@
 
 use crate::errors::DataFusionError;
 use crate::TokioRuntime;
+use datafusion_common::ScalarValue;
 use datafusion_expr::Volatility;
 use pyo3::prelude::*;
 use std::future::Future;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 
+/// How often a long running query checks for Python signals such as Ctrl-C
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A literal given from Python: a pyarrow scalar, a `ScalarValue`, or a plain Python
+/// value converted with `pyarrow.scalar` like `literal()` does
+pub struct LiteralValue(pub ScalarValue);
+
+impl<'py> FromPyObject<'py> for LiteralValue {
+    fn extract_bound(value: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if value.hasattr("type")? && value.hasattr("as_py")? {
+            return Ok(Self(value.extract()?));
+        }
+        let scalar = value
+            .py()
+            .import_bound("pyarrow")?
+            .call_method1("scalar", (value,))?;
+        Ok(Self(scalar.extract()?))
+    }
+}
+
 /// Utility to get the Tokio Runtime from Python
 pub(crate) fn get_tokio_runtime(py: Python) -> PyRef<TokioRuntime> {
     let datafusion = py.import_bound("datafusion._internal").unwrap();
@@ -46,6 +68,29 @@ where
     py.allow_threads(|| runtime.block_on(f))
 }
 
+/// Like [`wait_for_future`], but checks for Python signals while waiting, so a
+/// Ctrl-C raises `KeyboardInterrupt` and drops the future, cancelling the query
+pub(crate) fn wait_for_future_interruptible<F>(py: Python, f: F) -> PyResult<F::Output>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    let runtime: &Runtime = &get_tokio_runtime(py).0;
+    py.allow_threads(|| {
+        runtime.block_on(async {
+            tokio::pin!(f);
+            loop {
+                tokio::select! {
+                    output = &mut f => return Ok(output),
+                    _ = tokio::time::sleep(SIGNAL_CHECK_INTERVAL) => {
+                        Python::with_gil(|py| py.check_signals())?;
+                    }
+                }
+            }
+        })
+    })
+}
+
 pub(crate) fn parse_volatility(value: &str) -> Result<Volatility, DataFusionError> {
     Ok(match value {
         "immutable" => Volatility::Immutable,